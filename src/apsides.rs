@@ -0,0 +1,384 @@
+/*
+  Apsides - perigee and apogee, the Moon's nearest and furthest points
+  from the Earth, after Meeus, Astronomical Algorithms chapter 50.
+
+  The times are good to a few minutes and the distances to a few km,
+  which is as well as the series goes.  The supermoon and micromoon tests
+  measure the Moon at new or full with meeus::moon_distance.
+*/
+
+use super::{
+    checked, datetime, dcos, dsin, jdaytosecs, jtime, meeus, timescale, PhaseEvent, PhaseKind,
+    PomError, EARTH_RADIUS,
+};
+use chrono::{DateTime, TimeZone, Utc};
+
+// ApsisKind - nearest or furthest
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApsisKind {
+    Perigee,
+    Apogee,
+}
+
+impl std::fmt::Display for ApsisKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ApsisKind::Perigee => write!(f, "Perigee"),
+            ApsisKind::Apogee => write!(f, "Apogee"),
+        }
+    }
+}
+
+// Apsis - one perigee or apogee: when it happens (seconds since 1970,
+// unless mapped into a chrono DateTime) and how far away the Moon is
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Apsis<T = f64> {
+    pub kind: ApsisKind,
+    pub time: T,
+    pub dist: f64, // distance of the Moon from the centre of the Earth, km
+}
+
+impl Apsis {
+    // new - the apsis k, counted in halves from the perigee of 1999
+    // December 22: whole k are perigees, half k apogees
+    fn new(k: f64) -> Apsis {
+        let (jde, parallax) = apsis(k);
+        Apsis {
+            kind: if k.fract() == 0.0 {
+                ApsisKind::Perigee
+            } else {
+                ApsisKind::Apogee
+            },
+            time: jdaytosecs(timescale::tt_to_utc(jde)),
+            dist: EARTH_RADIUS / (parallax / 3600.0).to_radians().sin(),
+        }
+    }
+
+    // with_timezone - the same event as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> Result<Apsis<DateTime<Tz>>, PomError> {
+        Ok(Apsis {
+            kind: self.kind,
+            time: datetime(self.time, tz)?,
+            dist: self.dist,
+        })
+    }
+}
+
+impl<T> Apsis<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Apsis<U> {
+        Apsis {
+            kind: self.kind,
+            time: f(self.time),
+            dist: self.dist,
+        }
+    }
+}
+
+// apsis - Julian Ephemeris Day and equatorial horizontal parallax
+// (arc seconds) of apsis k
+
+fn apsis(k: f64) -> (f64, f64) {
+    let t = k / 1325.55; // time in Julian centuries from 2000 January 1.5
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    // mean time of the apsis
+    let jde =
+        2451534.6698 + 27.55454989 * k - 0.0006691 * t2 - 0.000001098 * t3 + 0.0000000052 * t4;
+
+    // Moon's mean elongation, Sun's mean anomaly, Moon's argument of latitude
+    let d = 171.9179 + 335.9106046 * k - 0.0100383 * t2 - 0.00001156 * t3 + 0.000000055 * t4;
+    let m = 347.3477 + 27.1577721 * k - 0.0008130 * t2 - 0.0000010 * t3;
+    let f = 316.6109 + 364.5287911 * k - 0.0125053 * t2 - 0.0000148 * t3;
+
+    if k.fract() == 0.0 {
+        let correction = -1.6769 * dsin(2.0 * d) + 0.4589 * dsin(4.0 * d) - 0.1856 * dsin(6.0 * d)
+            + 0.0883 * dsin(8.0 * d)
+            + (-0.0773 + 0.00019 * t) * dsin(2.0 * d - m)
+            + (0.0502 - 0.00013 * t) * dsin(m)
+            - 0.0460 * dsin(10.0 * d)
+            + (0.0422 - 0.00011 * t) * dsin(4.0 * d - m)
+            - 0.0256 * dsin(6.0 * d - m)
+            + 0.0253 * dsin(12.0 * d)
+            + 0.0237 * dsin(d)
+            + 0.0162 * dsin(8.0 * d - m)
+            - 0.0145 * dsin(14.0 * d)
+            + 0.0129 * dsin(2.0 * f)
+            - 0.0112 * dsin(3.0 * d)
+            - 0.0104 * dsin(10.0 * d - m)
+            + 0.0086 * dsin(16.0 * d)
+            + 0.0069 * dsin(12.0 * d - m)
+            + 0.0066 * dsin(5.0 * d)
+            - 0.0053 * dsin(2.0 * d + 2.0 * f)
+            - 0.0052 * dsin(18.0 * d)
+            - 0.0046 * dsin(14.0 * d - m)
+            - 0.0041 * dsin(7.0 * d)
+            + 0.0040 * dsin(2.0 * d + m)
+            + 0.0032 * dsin(20.0 * d)
+            - 0.0032 * dsin(d + m)
+            + 0.0031 * dsin(16.0 * d - m)
+            - 0.0029 * dsin(4.0 * d + m)
+            + 0.0027 * dsin(9.0 * d)
+            + 0.0027 * dsin(4.0 * d + 2.0 * f)
+            - 0.0027 * dsin(2.0 * d - 2.0 * m)
+            + 0.0024 * dsin(4.0 * d - 2.0 * m)
+            - 0.0021 * dsin(6.0 * d - 2.0 * m)
+            - 0.0021 * dsin(22.0 * d)
+            - 0.0021 * dsin(18.0 * d - m)
+            + 0.0019 * dsin(6.0 * d + m)
+            - 0.0018 * dsin(11.0 * d)
+            - 0.0014 * dsin(8.0 * d + m)
+            - 0.0014 * dsin(4.0 * d - 2.0 * f)
+            - 0.0014 * dsin(6.0 * d + 2.0 * f)
+            + 0.0014 * dsin(3.0 * d + m)
+            - 0.0014 * dsin(5.0 * d + m)
+            + 0.0013 * dsin(13.0 * d)
+            + 0.0013 * dsin(20.0 * d - m)
+            + 0.0011 * dsin(3.0 * d + 2.0 * m)
+            - 0.0011 * dsin(4.0 * d + 2.0 * f - 2.0 * m)
+            - 0.0010 * dsin(d + 2.0 * m)
+            - 0.0009 * dsin(22.0 * d - m)
+            - 0.0008 * dsin(4.0 * f)
+            + 0.0008 * dsin(6.0 * d - 2.0 * f)
+            + 0.0008 * dsin(2.0 * d - 2.0 * f + m)
+            + 0.0007 * dsin(2.0 * m)
+            + 0.0007 * dsin(2.0 * f - m)
+            + 0.0007 * dsin(2.0 * d + 4.0 * f)
+            - 0.0006 * dsin(2.0 * f - 2.0 * m)
+            - 0.0006 * dsin(2.0 * d - 2.0 * f + 2.0 * m)
+            + 0.0006 * dsin(24.0 * d)
+            + 0.0005 * dsin(4.0 * d - 4.0 * f)
+            + 0.0005 * dsin(2.0 * d + 2.0 * m)
+            - 0.0004 * dsin(d - m);
+
+        let parallax = 3629.215 + 63.224 * dcos(2.0 * d) - 6.990 * dcos(4.0 * d)
+            + (2.834 - 0.0071 * t) * dcos(2.0 * d - m)
+            + 1.927 * dcos(6.0 * d)
+            - 1.263 * dcos(d)
+            - 0.702 * dcos(8.0 * d)
+            + (0.696 - 0.0017 * t) * dcos(m)
+            - 0.690 * dcos(2.0 * f)
+            + (-0.629 + 0.0016 * t) * dcos(4.0 * d - m)
+            - 0.392 * dcos(2.0 * d - 2.0 * f)
+            + 0.297 * dcos(10.0 * d)
+            + 0.260 * dcos(6.0 * d - m)
+            + 0.201 * dcos(3.0 * d)
+            - 0.161 * dcos(2.0 * d + m)
+            + 0.157 * dcos(d + m)
+            - 0.138 * dcos(12.0 * d)
+            - 0.127 * dcos(8.0 * d - m)
+            + 0.104 * dcos(2.0 * d + 2.0 * f)
+            + 0.104 * dcos(2.0 * d - 2.0 * m)
+            - 0.079 * dcos(5.0 * d)
+            + 0.068 * dcos(14.0 * d)
+            + 0.067 * dcos(10.0 * d - m)
+            + 0.054 * dcos(4.0 * d + m)
+            - 0.038 * dcos(12.0 * d - m)
+            - 0.038 * dcos(4.0 * d - 2.0 * m)
+            + 0.037 * dcos(7.0 * d)
+            - 0.037 * dcos(4.0 * d + 2.0 * f)
+            - 0.035 * dcos(16.0 * d)
+            - 0.030 * dcos(3.0 * d + m)
+            + 0.029 * dcos(d - m)
+            - 0.025 * dcos(6.0 * d + m)
+            + 0.023 * dcos(2.0 * m)
+            + 0.023 * dcos(14.0 * d - m)
+            - 0.023 * dcos(2.0 * d + 2.0 * m)
+            + 0.022 * dcos(6.0 * d - 2.0 * m)
+            - 0.021 * dcos(2.0 * d - 2.0 * f - m)
+            - 0.020 * dcos(9.0 * d)
+            + 0.019 * dcos(18.0 * d)
+            + 0.017 * dcos(6.0 * d + 2.0 * f)
+            + 0.014 * dcos(2.0 * f - m)
+            - 0.014 * dcos(16.0 * d - m)
+            + 0.013 * dcos(4.0 * d - 2.0 * f)
+            + 0.012 * dcos(8.0 * d + m)
+            + 0.011 * dcos(11.0 * d)
+            + 0.010 * dcos(5.0 * d + m)
+            - 0.010 * dcos(20.0 * d);
+
+        (jde + correction, parallax)
+    } else {
+        let correction = 0.4392 * dsin(2.0 * d)
+            + 0.0684 * dsin(4.0 * d)
+            + (0.0456 - 0.00011 * t) * dsin(m)
+            + (0.0426 - 0.00011 * t) * dsin(2.0 * d - m)
+            + 0.0212 * dsin(2.0 * f)
+            - 0.0189 * dsin(d)
+            + 0.0144 * dsin(6.0 * d)
+            + 0.0113 * dsin(4.0 * d - m)
+            + 0.0047 * dsin(2.0 * d + 2.0 * f)
+            + 0.0036 * dsin(d + m)
+            + 0.0035 * dsin(8.0 * d)
+            + 0.0034 * dsin(6.0 * d - m)
+            - 0.0034 * dsin(2.0 * d - 2.0 * f)
+            + 0.0022 * dsin(2.0 * d - 2.0 * m)
+            - 0.0017 * dsin(3.0 * d)
+            + 0.0013 * dsin(4.0 * d + 2.0 * f)
+            + 0.0011 * dsin(8.0 * d - m)
+            + 0.0010 * dsin(4.0 * d - 2.0 * m)
+            + 0.0009 * dsin(10.0 * d)
+            + 0.0007 * dsin(3.0 * d + m)
+            + 0.0006 * dsin(2.0 * m)
+            + 0.0005 * dsin(2.0 * d + m)
+            + 0.0005 * dsin(2.0 * d + 2.0 * m)
+            + 0.0004 * dsin(6.0 * d + 2.0 * f)
+            + 0.0004 * dsin(6.0 * d - 2.0 * m)
+            + 0.0004 * dsin(10.0 * d - m)
+            - 0.0004 * dsin(5.0 * d)
+            - 0.0004 * dsin(4.0 * d - 2.0 * f)
+            + 0.0003 * dsin(2.0 * f + m)
+            + 0.0003 * dsin(12.0 * d)
+            + 0.0003 * dsin(2.0 * d + 2.0 * f - m)
+            - 0.0003 * dsin(d - m);
+
+        let parallax = 3245.251 - 9.147 * dcos(2.0 * d) - 0.841 * dcos(d)
+            + 0.697 * dcos(2.0 * f)
+            + (-0.656 + 0.0016 * t) * dcos(m)
+            + 0.355 * dcos(4.0 * d)
+            + 0.159 * dcos(2.0 * d - m)
+            + 0.127 * dcos(d + m)
+            + 0.065 * dcos(4.0 * d - m)
+            + 0.052 * dcos(6.0 * d)
+            + 0.043 * dcos(2.0 * d + m)
+            + 0.031 * dcos(2.0 * d + 2.0 * f)
+            - 0.023 * dcos(2.0 * d - 2.0 * f)
+            + 0.022 * dcos(2.0 * d - 2.0 * m)
+            + 0.019 * dcos(2.0 * d + 2.0 * m)
+            - 0.016 * dcos(2.0 * m)
+            + 0.014 * dcos(6.0 * d - m)
+            + 0.010 * dcos(8.0 * d);
+
+        (jde + correction, parallax)
+    }
+}
+
+// apsis_k - the apsis (in halves) whose mean time falls just before t
+// (seconds since 1970).  Beyond the dates chrono can hold the series is
+// no longer even in order, so those are refused.
+
+fn apsis_k(t: f64) -> Result<f64, PomError> {
+    datetime(t, &Utc)?;
+    let jde = timescale::utc_to_tt(jtime(t));
+    Ok(((jde - 2451534.6698) / 27.55454989 * 2.0).floor() / 2.0)
+}
+
+// Apsides - an endless run of perigees and apogees, forward or backward
+// in time
+
+#[derive(Debug, Clone)]
+pub struct Apsides {
+    k: f64,        // the next apsis
+    forward: bool, // which way we are walking
+}
+
+impl Iterator for Apsides {
+    type Item = Apsis;
+
+    fn next(&mut self) -> Option<Apsis> {
+        let apsis = Apsis::new(self.k);
+        self.k += if self.forward { 0.5 } else { -0.5 };
+        Some(apsis)
+    }
+}
+
+// apsides_from - every perigee and apogee on or after t (seconds since
+// 1970), in ascending order of time, without end
+
+pub fn apsides_from(t: f64) -> Result<Apsides, PomError> {
+    // the corrections move an apsis by no more than a few days, well
+    // short of a whole step
+    let mut k = apsis_k(t)? - 0.5;
+    for _ in 0..8 {
+        if Apsis::new(k).time >= t {
+            return Ok(Apsides { k, forward: true });
+        }
+        k += 0.5;
+    }
+    Err(PomError::NoConvergence)
+}
+
+// apsides_before - every perigee and apogee before t (seconds since
+// 1970), in descending order of time, without end
+
+pub fn apsides_before(t: f64) -> Result<Apsides, PomError> {
+    let mut k = apsis_k(t)? + 1.0;
+    for _ in 0..8 {
+        if Apsis::new(k).time < t {
+            return Ok(Apsides { k, forward: false });
+        }
+        k -= 0.5;
+    }
+    Err(PomError::NoConvergence)
+}
+
+// apsislist - every perigee and apogee from sdate up to edate (seconds
+// since 1970)
+
+pub fn apsislist(sdate: f64, edate: f64) -> Result<Vec<Apsis>, PomError> {
+    checked(edate)?;
+    Ok(apsides_from(sdate)?
+        .take_while(|apsis| apsis.time < edate)
+        .collect())
+}
+
+// MoonSize - a new or full moon unusually near or far
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoonSize {
+    Supermoon,
+    Micromoon,
+}
+
+// SizeRule - what counts as a supermoon or micromoon
+//
+//   Nolle      within 10% of the way from the perigee to the apogee of
+//              that orbit (Nolle's "within 90% of its closest approach"),
+//              and the same from the apogee for a micromoon
+//   Distance   nearer than perigee km, or further than apogee km
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeRule {
+    Nolle,
+    Distance { perigee: f64, apogee: f64 },
+}
+
+// moon_size - whether event is a supermoon or micromoon under rule.
+// Quarters are neither.
+
+pub fn moon_size(event: &PhaseEvent, rule: SizeRule) -> Result<Option<MoonSize>, PomError> {
+    if !matches!(event.kind, PhaseKind::New | PhaseKind::Full) {
+        return Ok(None);
+    }
+    let dist = meeus::moon_distance(timescale::utc_to_tt(jtime(checked(event.time)?)));
+
+    let (perigee, apogee) = match rule {
+        SizeRule::Distance { perigee, apogee } => (perigee, apogee),
+        SizeRule::Nolle => {
+            // the perigee and apogee nearest the event
+            let nearest = |kind: ApsisKind| -> Result<f64, PomError> {
+                let after = apsides_from(event.time)?.find(|a| a.kind == kind);
+                let before = apsides_before(event.time)?.find(|a| a.kind == kind);
+                match (before, after) {
+                    (Some(b), Some(a)) if event.time - b.time < a.time - event.time => Ok(b.dist),
+                    (_, Some(a)) => Ok(a.dist),
+                    _ => Err(PomError::NoConvergence),
+                }
+            };
+            let (perigee, apogee) = (nearest(ApsisKind::Perigee)?, nearest(ApsisKind::Apogee)?);
+            let tenth = (apogee - perigee) / 10.0;
+            (perigee + tenth, apogee - tenth)
+        }
+    };
+
+    Ok(if dist <= perigee {
+        Some(MoonSize::Supermoon)
+    } else if dist >= apogee {
+        Some(MoonSize::Micromoon)
+    } else {
+        None
+    })
+}
@@ -0,0 +1,105 @@
+/*
+  Blue moons, of which there are two kinds:
+
+    calendar   the second full moon in a calendar month, which depends
+               on the time zone the month is kept in
+    seasonal   the third full moon of a season with four, as the Maine
+               Farmers' Almanac had it, seasons running from equinox to
+               solstice and back
+*/
+
+use super::{
+    checked, datetime, phaselist, phases_before, seasons, PhaseEvent, PhaseKind, PomError,
+};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+
+// BlueMoon - which definitions make a full moon blue
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BlueMoon {
+    pub calendar: bool,
+    pub seasonal: bool,
+}
+
+impl BlueMoon {
+    // any - blue by either definition
+    pub fn any(&self) -> bool {
+        self.calendar || self.seasonal
+    }
+}
+
+// blue_moon - whether event is a blue moon, with calendar months kept in
+// tz.  Only full moons can be.
+
+pub fn blue_moon<Tz: TimeZone>(event: &PhaseEvent, tz: &Tz) -> Result<BlueMoon, PomError> {
+    checked(event.time)?;
+    if event.kind != PhaseKind::Full {
+        return Ok(BlueMoon::default());
+    }
+    Ok(BlueMoon {
+        calendar: calendar_blue(event, tz)?,
+        seasonal: seasonal_blue(event)?,
+    })
+}
+
+// blue_moons - every blue moon on or after sdate but before edate
+// (seconds since 1970), with calendar months kept in tz
+
+pub fn blue_moons<Tz: TimeZone>(
+    sdate: f64,
+    edate: f64,
+    tz: &Tz,
+) -> Result<Vec<(PhaseEvent, BlueMoon)>, PomError> {
+    let mut blue = Vec::new();
+    for event in phaselist(sdate, edate)? {
+        let kind = blue_moon(&event, tz)?;
+        if kind.any() {
+            blue.push((event, kind));
+        }
+    }
+    Ok(blue)
+}
+
+// calendar_blue - whether the full moon before event fell in the same
+// month in tz.  The search starts an hour early, and skips event's own
+// lunation, so that the same full moon from a different model never
+// counts twice.
+
+fn calendar_blue<Tz: TimeZone>(event: &PhaseEvent, tz: &Tz) -> Result<bool, PomError> {
+    let previous = match phases_before(event.time - 3600.0)?
+        .find(|e| e.kind == PhaseKind::Full && e.lunation != event.lunation)
+    {
+        Some(previous) => previous,
+        None => return Ok(false),
+    };
+    let month = |t: f64| -> Result<(i32, u32), PomError> {
+        let dt: DateTime<Tz> = datetime(t, tz)?;
+        Ok((dt.year(), dt.month()))
+    };
+    Ok(month(previous.time)? == month(event.time)?)
+}
+
+// seasonal_blue - whether event is the third of four full moons in its
+// season
+
+fn seasonal_blue(event: &PhaseEvent) -> Result<bool, PomError> {
+    let year = datetime(event.time, &Utc)?.year();
+    let mut starts = Vec::with_capacity(12);
+    for y in year - 1..=year + 1 {
+        starts.extend(seasons::seasons(y)?.iter().map(|e| e.time));
+    }
+    let season = match starts
+        .windows(2)
+        .find(|w| w[0] <= event.time && event.time < w[1])
+    {
+        Some(season) => season,
+        None => return Ok(false),
+    };
+
+    let full: Vec<f64> = phaselist(season[0], season[1])?
+        .into_iter()
+        .filter(|e| e.kind == PhaseKind::Full)
+        .map(|e| e.time)
+        .collect();
+    Ok(full.len() == 4 && (full[2] - event.time).abs() < 3600.0)
+}
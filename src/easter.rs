@@ -0,0 +1,171 @@
+/*
+  Easter, the first Sunday after the Paschal full moon, which is the
+  first full moon on or after the March equinox.
+
+  The churches do not look at the sky for this: the computus works the
+  Paschal full moon out from the year's place in the 19 year Metonic
+  cycle (its golden number) and the age of the Moon on 1 January (the
+  epact), taking the equinox as 21 March.  The Gregorian computus keeps
+  its calendar and Moon in step with the sky; the Julian one, still used
+  by the Orthodox churches, has drifted days out with both.
+
+  The astronomical Easter (the reckoning the World Council of Churches
+  proposed in 1997) takes the real equinox and full moon instead, dated
+  on the meridian of Jerusalem.
+*/
+
+use super::seasons::{season, SeasonEvent, SeasonKind};
+use super::{datetime, phases_from_with, Meeus, PhaseEvent, PhaseKind, PomError};
+use chrono::{Datelike, NaiveDate, Utc};
+
+// Jerusalem, 35.2317 degrees east, as an offset from UTC in seconds
+const JERUSALEM: f64 = 8456.0;
+
+// Computus - which church's reckoning
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Computus {
+    Gregorian,
+    Julian,
+}
+
+// Easter - Easter as the computus finds it.  The dates are in the
+// (proleptic) Gregorian calendar even for the Julian computus, so that
+// the two can be compared; the Julian calendar date is 13 days earlier
+// in this century.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Easter {
+    pub computus: Computus,
+    pub golden_number: u32,           // 1 to 19
+    pub epact: u32,                   // age of the Moon on 1 January, days
+    pub paschal_full_moon: NaiveDate, // the ecclesiastical full moon
+    pub sunday: NaiveDate,            // Easter Sunday
+}
+
+// easter - Easter of year by computus; the Gregorian computus from 1583,
+// the Julian from AD 326
+
+pub fn easter(year: i32, computus: Computus) -> Result<Easter, PomError> {
+    representable(year)?;
+    let golden_number = (year.rem_euclid(19) + 1) as u32;
+    match computus {
+        Computus::Gregorian => {
+            if year < 1583 {
+                return Err(PomError::InvalidYear(year));
+            }
+            // Lilius' and Clavius' solar and lunar corrections by century
+            let g = golden_number as i32;
+            let c = year / 100 + 1;
+            let x = 3 * c / 4 - 12;
+            let z = (8 * c + 5) / 25 - 5;
+            let d = 5 * year / 4 - x - 10; // March (-d) mod 7 is a Sunday
+
+            let mut epact = (11 * g + 20 + z - x).rem_euclid(30);
+            if (epact == 25 && g > 11) || epact == 24 {
+                epact += 1;
+            }
+
+            // Paschal full moon on March n
+            let mut n = 44 - epact;
+            if n < 21 {
+                n += 30;
+            }
+            let sunday = n + 7 - (d + n) % 7;
+
+            Ok(Easter {
+                computus,
+                golden_number,
+                epact: epact as u32,
+                paschal_full_moon: march(year, n as u32)?,
+                sunday: march(year, sunday as u32)?,
+            })
+        }
+        Computus::Julian => {
+            if year < 326 {
+                return Err(PomError::InvalidYear(year));
+            }
+            let d = (19 * year.rem_euclid(19) + 15) % 30; // full moon on March 21 + d
+            let e = (2 * year.rem_euclid(4) + 4 * year.rem_euclid(7) - d + 34) % 7;
+            Ok(Easter {
+                computus,
+                golden_number,
+                epact: (11 * (golden_number - 1)) % 30,
+                paschal_full_moon: julian_march(year, 21 + d as u32)?,
+                sunday: julian_march(year, 22 + (d + e) as u32)?,
+            })
+        }
+    }
+}
+
+// representable - whether chrono can date year, so that nothing below
+// overflows
+
+fn representable(year: i32) -> Result<(), PomError> {
+    if (NaiveDate::MIN.year()..=NaiveDate::MAX.year()).contains(&year) {
+        Ok(())
+    } else {
+        Err(PomError::InvalidYear(year))
+    }
+}
+
+// march - day n of March in year (Gregorian), running on into April
+
+fn march(year: i32, n: u32) -> Result<NaiveDate, PomError> {
+    NaiveDate::from_ymd_opt(year, 3, 1)
+        .and_then(|d| d.checked_add_days(chrono::Days::new(n as u64 - 1)))
+        .ok_or(PomError::InvalidYear(year))
+}
+
+// julian_march - day n of March in year of the Julian calendar, as a
+// Gregorian date
+
+fn julian_march(year: i32, n: u32) -> Result<NaiveDate, PomError> {
+    // Julian day number of 1 March, then to chrono's days from 1 January
+    // of year 1
+    let y = year as i64 + 4800;
+    let jdn = 1 + 365 * y + y / 4 - 32083;
+    NaiveDate::from_num_days_from_ce_opt((jdn - 1721425 + n as i64 - 1) as i32)
+        .ok_or(PomError::InvalidYear(year))
+}
+
+// AstronomicalEaster - Easter from the sky: the March equinox, the first
+// full moon after it and the Sunday after that, dated in Jerusalem, and
+// whether that Sunday differs from the Gregorian Easter
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AstronomicalEaster {
+    pub equinox: SeasonEvent,
+    pub paschal_full_moon: PhaseEvent,
+    pub sunday: NaiveDate,
+    pub disagrees: bool,
+}
+
+// astronomical_easter - the astronomical Easter of year
+
+pub fn astronomical_easter(year: i32) -> Result<AstronomicalEaster, PomError> {
+    representable(year)?;
+    let equinox = season(year, SeasonKind::MarchEquinox)?;
+    let paschal_full_moon = phases_from_with(Meeus, equinox.time)?
+        .find(|e| e.kind == PhaseKind::Full)
+        .ok_or(PomError::NoConvergence)?;
+
+    let day = datetime(paschal_full_moon.time + JERUSALEM, &Utc)?.date_naive();
+    let sunday = day
+        .checked_add_days(chrono::Days::new(
+            7 - day.weekday().num_days_from_sunday() as u64,
+        ))
+        .ok_or(PomError::InvalidYear(year))?;
+
+    let disagrees = match easter(year, Computus::Gregorian) {
+        Ok(e) => e.sunday != sunday,
+        Err(_) => false,
+    };
+
+    Ok(AstronomicalEaster {
+        equinox,
+        paschal_full_moon,
+        sunday,
+        disagrees,
+    })
+}
@@ -0,0 +1,711 @@
+/*
+  Eclipses, after Meeus, Astronomical Algorithms chapter 54.
+
+  An eclipse needs a new or full moon close to one of the nodes of the
+  Moon's orbit, where it crosses the ecliptic.  For each candidate the
+  series below give the time of greatest eclipse and gamma, the least
+  distance of the axis of the Moon's shadow (or of the Moon, from the axis
+  of the Earth's shadow) from the centre of the Earth in Earth radii,
+  along with u, the radius of the umbral cone there.  Everything else
+  follows from those two.
+
+  Times are good to a few minutes, which is plenty to say which eclipse,
+  of what kind, and when to look.
+
+  Local circumstances need more: the Besselian elements, which describe
+  the Moon's shadow on the fundamental plane through the centre of the
+  Earth square to the shadow's axis.  Rather than fit the usual
+  polynomials these are worked out afresh at each instant from the
+  chapter 47 Moon and chapter 25 Sun, and the contacts found by sampling
+  and bisection as for rising and setting.  They come out within a
+  minute or so, the Sun being the weaker of the two.
+*/
+
+use super::observer::{gmst, Observer};
+use super::position::{obliquity, Equatorial};
+use super::riseset::{bisect, crossings};
+use super::{
+    checked, datetime, dcos, dsin, jdaytosecs, jtime, meeus, phases_from_with, timescale, todeg,
+    Meeus, PhaseEvent, PhaseKind, PomError, EARTH_RADIUS, SUNANGSIZ, SUNSMAX,
+};
+use chrono::{DateTime, TimeZone};
+
+// Syzygy - what chapter 54 has to say about one new or full moon
+
+#[derive(Debug, Clone, Copy)]
+struct Syzygy {
+    jde: f64,   // greatest eclipse, Julian Ephemeris Day
+    gamma: f64, // least distance of the shadow axis from the Earth's centre
+    u: f64,     // radius of the umbral cone in the fundamental plane
+    mp: f64,    // Moon's mean anomaly, degrees
+}
+
+// syzygy - the new (phase 0.0) or full (phase 0.5) moon of lunation k
+// (counted, as everywhere, from the new moon of 1900 January), or None
+// if the Moon is too far from a node for any eclipse
+
+fn syzygy(k: f64, phase: f64) -> Option<Syzygy> {
+    let k = k - 1237.0 + phase; // Meeus' k
+    let t = k / 1236.85;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+    let full = phase > 0.25;
+
+    // the Moon's argument of latitude tells how near the node we are
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t2 - 0.00000227 * t3 + 0.000000011 * t4;
+    if dsin(f).abs() > 0.36 {
+        return None;
+    }
+
+    let mean =
+        2451550.09766 + 29.530588861 * k + 0.00015437 * t2 - 0.000000150 * t3 + 0.00000000073 * t4;
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t2 - 0.00000011 * t3;
+    let mp = 201.5643 + 385.81693528 * k + 0.0107582 * t2 + 0.00001238 * t3 - 0.000000058 * t4;
+    let om = 124.7746 - 1.56375588 * k + 0.0020672 * t2 + 0.00000215 * t3;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+    let f1 = f - 0.02665 * dsin(om);
+    let a1 = 299.77 + 0.107408 * k - 0.009173 * t2;
+
+    let (c1, c2) = if full {
+        (-0.4065, 0.1727)
+    } else {
+        (-0.4075, 0.1721)
+    };
+    let jde = mean + c1 * dsin(mp) + c2 * e * dsin(m) + 0.0161 * dsin(2.0 * mp)
+        - 0.0097 * dsin(2.0 * f1)
+        + 0.0073 * e * dsin(mp - m)
+        - 0.0050 * e * dsin(mp + m)
+        - 0.0023 * dsin(mp - 2.0 * f1)
+        + 0.0021 * e * dsin(2.0 * m)
+        + 0.0012 * dsin(mp + 2.0 * f1)
+        + 0.0006 * e * dsin(2.0 * mp + m)
+        - 0.0004 * dsin(3.0 * mp)
+        - 0.0003 * e * dsin(m + 2.0 * f1)
+        + 0.0003 * dsin(a1)
+        - 0.0002 * e * dsin(m - 2.0 * f1)
+        - 0.0002 * e * dsin(2.0 * mp - m)
+        - 0.0002 * dsin(om);
+
+    let p = 0.2070 * e * dsin(m) + 0.0024 * e * dsin(2.0 * m) - 0.0392 * dsin(mp)
+        + 0.0116 * dsin(2.0 * mp)
+        - 0.0073 * e * dsin(mp + m)
+        + 0.0067 * e * dsin(mp - m)
+        + 0.0118 * dsin(2.0 * f1);
+    let q = 5.2207 - 0.0048 * e * dcos(m) + 0.0020 * e * dcos(2.0 * m)
+        - 0.3299 * dcos(mp)
+        - 0.0060 * e * dcos(mp + m)
+        + 0.0041 * e * dcos(mp - m);
+    let w = dcos(f1).abs();
+    let gamma = (p * dcos(f1) + q * dsin(f1)) * (1.0 - 0.0048 * w);
+    let u = 0.0059 + 0.0046 * e * dcos(m) - 0.0182 * dcos(mp) + 0.0004 * dcos(2.0 * mp)
+        - 0.0005 * dcos(m + mp);
+
+    Some(Syzygy { jde, gamma, u, mp })
+}
+
+// seconds - Julian Ephemeris Day jde as seconds since 1970 (UTC)
+
+fn seconds(jde: f64) -> f64 {
+    jdaytosecs(timescale::tt_to_utc(jde))
+}
+
+// LunarEclipseKind - how deep the Moon goes into the Earth's shadow
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LunarEclipseKind {
+    Penumbral,
+    Partial,
+    Total,
+}
+
+impl std::fmt::Display for LunarEclipseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LunarEclipseKind::Penumbral => write!(f, "Penumbral"),
+            LunarEclipseKind::Partial => write!(f, "Partial"),
+            LunarEclipseKind::Total => write!(f, "Total"),
+        }
+    }
+}
+
+// LunarEclipse - one eclipse of the Moon.  The contacts are the Moon
+// entering (p1) and leaving (p4) the penumbra, entering (u1) and leaving
+// (u4) the umbra, and the start (u2) and end (u3) of totality; the umbral
+// ones are None when the eclipse does not reach that far.  Magnitudes are
+// the fraction of the Moon's diameter inside each shadow at greatest
+// eclipse.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LunarEclipse<T = f64> {
+    pub kind: LunarEclipseKind,
+    pub greatest: T,
+    pub gamma: f64,
+    pub penumbral_magnitude: f64,
+    pub umbral_magnitude: f64,
+    pub p1: T,
+    pub u1: Option<T>,
+    pub u2: Option<T>,
+    pub u3: Option<T>,
+    pub u4: Option<T>,
+    pub p4: T,
+    pub lunation: i64,
+}
+
+impl LunarEclipse {
+    // with_timezone - the same eclipse with its times as DateTimes in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<LunarEclipse<DateTime<Tz>>, PomError> {
+        let dt = |t: Option<f64>| t.map(|t| datetime(t, tz)).transpose();
+        Ok(LunarEclipse {
+            kind: self.kind,
+            greatest: datetime(self.greatest, tz)?,
+            gamma: self.gamma,
+            penumbral_magnitude: self.penumbral_magnitude,
+            umbral_magnitude: self.umbral_magnitude,
+            p1: datetime(self.p1, tz)?,
+            u1: dt(self.u1)?,
+            u2: dt(self.u2)?,
+            u3: dt(self.u3)?,
+            u4: dt(self.u4)?,
+            p4: datetime(self.p4, tz)?,
+            lunation: self.lunation,
+        })
+    }
+}
+
+// lunar_eclipse - the eclipse at a full moon, if there is one.  Any other
+// phase has none.
+
+pub fn lunar_eclipse(event: &PhaseEvent) -> Result<Option<LunarEclipse>, PomError> {
+    checked(event.time)?;
+    if event.kind != PhaseKind::Full {
+        return Ok(None);
+    }
+    let s = match syzygy(event.lunation as f64, 0.5) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let g = s.gamma.abs();
+
+    let penumbral_magnitude = (1.5573 + s.u - g) / 0.5450;
+    let umbral_magnitude = (1.0128 - s.u - g) / 0.5450;
+    if penumbral_magnitude <= 0.0 {
+        return Ok(None);
+    }
+
+    // semidurations, in days, of the penumbral, partial and total phases
+    let n = 0.5458 + 0.0400 * dcos(s.mp);
+    let semi = |r: f64| (r * r - g * g).sqrt() / n / 24.0;
+    let penumbral = semi(1.5573 + s.u);
+    let partial = semi(1.0128 - s.u);
+    let total = semi(0.4678 - s.u);
+    let contact = |d: f64, sign: f64| {
+        if d.is_nan() {
+            None
+        } else {
+            Some(seconds(s.jde + sign * d))
+        }
+    };
+
+    Ok(Some(LunarEclipse {
+        kind: if umbral_magnitude >= 1.0 {
+            LunarEclipseKind::Total
+        } else if umbral_magnitude > 0.0 {
+            LunarEclipseKind::Partial
+        } else {
+            LunarEclipseKind::Penumbral
+        },
+        greatest: seconds(s.jde),
+        gamma: s.gamma,
+        penumbral_magnitude,
+        umbral_magnitude,
+        p1: seconds(s.jde - penumbral),
+        u1: contact(partial, -1.0),
+        u2: contact(total, -1.0),
+        u3: contact(total, 1.0),
+        u4: contact(partial, 1.0),
+        p4: seconds(s.jde + penumbral),
+        lunation: event.lunation,
+    }))
+}
+
+// lunar_eclipses - every lunar eclipse whose greatest eclipse falls on or
+// after sdate but before edate (seconds since 1970)
+
+pub fn lunar_eclipses(sdate: f64, edate: f64) -> Result<Vec<LunarEclipse>, PomError> {
+    let mut eclipses = Vec::new();
+    for event in candidates(sdate, edate, PhaseKind::Full)? {
+        if let Some(eclipse) = lunar_eclipse(&event)? {
+            if eclipse.greatest >= sdate && eclipse.greatest < edate {
+                eclipses.push(eclipse);
+            }
+        }
+    }
+    Ok(eclipses)
+}
+
+// radius of the Moon in Earth radii: the mean limb for the penumbra, and
+// the valleys of the limb, which let the last of the Sun through, for the
+// umbra
+const MOON_RADIUS: f64 = 0.2725076;
+const MOON_RADIUS_UMBRA: f64 = 0.272281;
+
+// how far either side of greatest eclipse to look for local contacts,
+// seconds
+const WINDOW: f64 = 4.0 * 3600.0;
+const STEP: f64 = 300.0;
+
+// Besselian - the Besselian elements at an instant.  x, y are the axis of
+// the shadow on the fundamental plane, l1, l2 the radii of the penumbra
+// and umbra there (l2 negative when the umbra reaches the plane), all in
+// Earth radii; d is the declination of the axis and mu its Greenwich hour
+// angle, f1 and f2 the angles of the penumbral and umbral cones.
+
+#[derive(Debug, Clone, Copy)]
+struct Besselian {
+    x: f64,
+    y: f64,
+    d: f64,
+    mu: f64,
+    l1: f64,
+    l2: f64,
+    tan_f1: f64,
+    tan_f2: f64,
+}
+
+// besselian - the Besselian elements at t (seconds since 1970)
+
+fn besselian(t: f64) -> Besselian {
+    let jde = timescale::utc_to_tt(jtime(t));
+    let eps = obliquity(jde);
+    let sun = meeus::sun_position(jde).to_equatorial(eps);
+    let moon = meeus::moon_position(jde).to_equatorial(eps);
+
+    // geocentric rectangular co-ordinates, Earth radii
+    let vector = |p: &Equatorial| {
+        let r = p.dist / EARTH_RADIUS;
+        [
+            r * dcos(p.dec) * dcos(p.ra),
+            r * dcos(p.dec) * dsin(p.ra),
+            r * dsin(p.dec),
+        ]
+    };
+    let (s, m) = (vector(&sun), vector(&moon));
+    let g = [s[0] - m[0], s[1] - m[1], s[2] - m[2]]; // Moon to Sun
+    let gl = (g[0] * g[0] + g[1] * g[1] + g[2] * g[2]).sqrt();
+
+    // right ascension and declination of the axis
+    let a = todeg(g[1].atan2(g[0]));
+    let d = todeg((g[2] / gl).asin());
+
+    let r = moon.dist / EARTH_RADIUS;
+    let h = moon.ra - a;
+    let x = r * dcos(moon.dec) * dsin(h);
+    let y = r * (dsin(moon.dec) * dcos(d) - dcos(moon.dec) * dsin(d) * dcos(h));
+    let z = r * (dsin(moon.dec) * dsin(d) + dcos(moon.dec) * dcos(d) * dcos(h));
+
+    let sun_radius = SUNSMAX * dsin(SUNANGSIZ / 2.0) / EARTH_RADIUS;
+    let sin_f1 = (sun_radius + MOON_RADIUS) / gl;
+    let sin_f2 = (sun_radius - MOON_RADIUS_UMBRA) / gl;
+    let cos_f1 = (1.0 - sin_f1 * sin_f1).sqrt();
+    let cos_f2 = (1.0 - sin_f2 * sin_f2).sqrt();
+
+    Besselian {
+        x,
+        y,
+        d,
+        mu: gmst(timescale::tt_to_ut1(jde)) - a,
+        l1: z * sin_f1 / cos_f1 + MOON_RADIUS / cos_f1,
+        l2: z * sin_f2 / cos_f2 - MOON_RADIUS_UMBRA / cos_f2,
+        tan_f1: sin_f1 / cos_f1,
+        tan_f2: sin_f2 / cos_f2,
+    }
+}
+
+// Shadow - the shadow as it falls on an observer: how far they are from
+// its axis and the radii of the penumbra and umbra at their distance
+// from the fundamental plane, Earth radii
+
+#[derive(Debug, Clone, Copy)]
+struct Shadow {
+    delta: f64,
+    l1: f64,
+    l2: f64,
+}
+
+fn shadow(t: f64, observer: &Observer) -> Shadow {
+    let b = besselian(t);
+    let (rho_sin, rho_cos) = observer.geocentric();
+    let h = b.mu + observer.lon;
+    let xi = rho_cos * dsin(h);
+    let eta = rho_sin * dcos(b.d) - rho_cos * dcos(h) * dsin(b.d);
+    let zeta = rho_sin * dsin(b.d) + rho_cos * dcos(h) * dcos(b.d);
+    Shadow {
+        delta: (b.x - xi).hypot(b.y - eta),
+        l1: b.l1 - zeta * b.tan_f1,
+        l2: b.l2 - zeta * b.tan_f2,
+    }
+}
+
+// sun_altitude - height of the Sun's centre above observer's horizon,
+// degrees, at t (seconds since 1970), ignoring refraction
+
+fn sun_altitude(t: f64, observer: &Observer) -> Result<f64, PomError> {
+    let jde = timescale::utc_to_tt(jtime(t));
+    let sun = meeus::sun_position(jde).to_equatorial(obliquity(jde));
+    Ok(observer.altitude_at(&sun, observer.local_sidereal_time(t)?))
+}
+
+// Contact - a moment of a local eclipse, and how high the Sun is then
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact<T = f64> {
+    pub time: T,
+    pub sun_altitude: f64, // degrees, ignoring refraction
+}
+
+impl Contact {
+    // with_timezone - the same contact with its time as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> Result<Contact<DateTime<Tz>>, PomError> {
+        Ok(Contact {
+            time: datetime(self.time, tz)?,
+            sun_altitude: self.sun_altitude,
+        })
+    }
+}
+
+// LocalEclipse - a solar eclipse seen from one place.  The Moon first
+// touches the Sun at c1 and leaves it at c4; inside the path of totality
+// or annularity c2 and c3 bound the total or annular phase.  The
+// magnitude is the fraction of the Sun's diameter covered at greatest
+// eclipse, the obscuration the fraction of its area.  The contacts are
+// worked out whether or not the Sun is up to see them.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalEclipse<T = f64> {
+    pub kind: SolarEclipseKind,
+    pub c1: Contact<T>,
+    pub c2: Option<Contact<T>>,
+    pub greatest: Contact<T>,
+    pub c3: Option<Contact<T>>,
+    pub c4: Contact<T>,
+    pub magnitude: f64,
+    pub obscuration: f64,
+}
+
+impl LocalEclipse {
+    // with_timezone - the same eclipse with its times as DateTimes in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<LocalEclipse<DateTime<Tz>>, PomError> {
+        Ok(LocalEclipse {
+            kind: self.kind,
+            c1: self.c1.with_timezone(tz)?,
+            c2: self.c2.map(|c| c.with_timezone(tz)).transpose()?,
+            greatest: self.greatest.with_timezone(tz)?,
+            c3: self.c3.map(|c| c.with_timezone(tz)).transpose()?,
+            c4: self.c4.with_timezone(tz)?,
+            magnitude: self.magnitude,
+            obscuration: self.obscuration,
+        })
+    }
+}
+
+impl<T> LocalEclipse<T> {
+    // in_path - whether the site is inside the path of totality or
+    // annularity, with the Sun up at greatest eclipse
+    pub fn in_path(&self) -> bool {
+        self.kind != SolarEclipseKind::Partial && self.greatest.sun_altitude > 0.0
+    }
+
+    // visible - whether the Sun is above the horizon at first or last
+    // contact, or at greatest eclipse
+    pub fn visible(&self) -> bool {
+        [&self.c1, &self.greatest, &self.c4]
+            .iter()
+            .any(|c| c.sun_altitude > 0.0)
+    }
+}
+
+// local_circumstances - eclipse as seen by observer, or None if the
+// shadow passes them by or the Sun is down throughout
+
+pub fn local_circumstances(
+    eclipse: &SolarEclipse,
+    observer: &Observer,
+) -> Result<Option<LocalEclipse>, PomError> {
+    let start = checked(eclipse.greatest)? - WINDOW;
+    let end = eclipse.greatest + WINDOW;
+    let penumbra = |t: f64| -> Result<f64, PomError> {
+        let s = shadow(t, observer);
+        Ok(s.delta - s.l1)
+    };
+    let umbra = |t: f64| -> Result<f64, PomError> {
+        let s = shadow(t, observer);
+        Ok(s.delta - s.l2.abs())
+    };
+
+    // greatest eclipse: the nearest sample to the axis, then a golden
+    // section search either side of it
+    let (mut best, mut least) = (start, penumbra(start)?);
+    let mut t = start + STEP;
+    while t <= end {
+        let p = penumbra(t)?;
+        if p < least {
+            (best, least) = (t, p);
+        }
+        t += STEP;
+    }
+    let (mut a, mut b) = ((best - STEP).max(start), (best + STEP).min(end));
+    const PHI: f64 = 0.618_033_988_749_895;
+    while b - a > 1.0 {
+        let c = b - PHI * (b - a);
+        let d = a + PHI * (b - a);
+        if penumbra(c)? < penumbra(d)? {
+            b = d;
+        } else {
+            a = c;
+        }
+    }
+    let greatest = (a + b) / 2.0;
+    let g = penumbra(greatest)?;
+    if g >= 0.0 {
+        return Ok(None);
+    }
+
+    // first and last contacts: the penumbra's edge crossing the observer
+    // on either side of greatest, found as rising and setting are
+    let c1 = crossings(start, greatest, penumbra)?
+        .into_iter()
+        .rev()
+        .find(|(_, outward)| !outward)
+        .map(|(t, _)| t)
+        .ok_or(PomError::NoConvergence)?;
+    let c4 = crossings(greatest, end, penumbra)?
+        .into_iter()
+        .find(|(_, outward)| *outward)
+        .map(|(t, _)| t)
+        .ok_or(PomError::NoConvergence)?;
+
+    // the shadow falls on the far side of the Earth as well, so a site
+    // where the Sun stays down from first contact to last sees nothing
+    let mut up = false;
+    let mut t = c1;
+    while !up && t < c4 {
+        up = sun_altitude(t, observer)? > 0.0;
+        t += STEP;
+    }
+    if !up && sun_altitude(c4, observer)? <= 0.0 {
+        return Ok(None);
+    }
+
+    let s = shadow(greatest, observer);
+    let central = s.delta < s.l2.abs();
+    let (c2, c3) = if central {
+        let u = umbra(greatest)?;
+        (
+            Some(bisect(c1, umbra(c1)?, greatest, umbra)?),
+            Some(bisect(greatest, u, c4, umbra)?),
+        )
+    } else {
+        (None, None)
+    };
+
+    let contact = |t: f64| -> Result<Contact, PomError> {
+        Ok(Contact {
+            time: t,
+            sun_altitude: sun_altitude(t, observer)?,
+        })
+    };
+    let magnitude = (s.l1 - s.delta) / (s.l1 + s.l2);
+
+    Ok(Some(LocalEclipse {
+        kind: match (central, s.l2 < 0.0) {
+            (false, _) => SolarEclipseKind::Partial,
+            (true, true) => SolarEclipseKind::Total,
+            (true, false) => SolarEclipseKind::Annular,
+        },
+        c1: contact(c1)?,
+        c2: c2.map(contact).transpose()?,
+        greatest: contact(greatest)?,
+        c3: c3.map(contact).transpose()?,
+        c4: contact(c4)?,
+        magnitude,
+        obscuration: obscuration(&s),
+    }))
+}
+
+// obscuration - fraction of the Sun's disc hidden by the Moon's, from the
+// shadow radii, which are the sum and difference of the two discs' radii
+// as seen from the site
+
+fn obscuration(s: &Shadow) -> f64 {
+    let sun = (s.l1 + s.l2) / 2.0;
+    let moon = (s.l1 - s.l2) / 2.0 / sun; // in radii of the Sun
+    let d = s.delta / sun; // centre to centre
+    if d >= 1.0 + moon {
+        0.0
+    } else if d <= (1.0 - moon).abs() {
+        moon.min(1.0).powi(2)
+    } else {
+        let a = ((d * d + 1.0 - moon * moon) / (2.0 * d)).acos();
+        let b = ((d * d + moon * moon - 1.0) / (2.0 * d * moon)).acos();
+        let lens = ((-d + 1.0 + moon) * (d + 1.0 - moon) * (d - 1.0 + moon) * (d + 1.0 + moon))
+            .sqrt()
+            / 2.0;
+        (a + moon * moon * b - lens) / std::f64::consts::PI
+    }
+}
+
+// local_eclipses - every solar eclipse seen from observer, with the Sun
+// above the horizon for some of it, whose greatest eclipse (over the
+// whole Earth) falls on or after sdate but before edate
+
+pub fn local_eclipses(
+    sdate: f64,
+    edate: f64,
+    observer: &Observer,
+) -> Result<Vec<LocalEclipse>, PomError> {
+    let mut eclipses = Vec::new();
+    for eclipse in solar_eclipses(sdate, edate)? {
+        if let Some(local) = local_circumstances(&eclipse, observer)? {
+            if local.visible() {
+                eclipses.push(local);
+            }
+        }
+    }
+    Ok(eclipses)
+}
+
+// candidates - the phases of kind from a day before sdate to a day after
+// edate, greatest eclipse being a little way from the true phase
+
+fn candidates(sdate: f64, edate: f64, kind: PhaseKind) -> Result<Vec<PhaseEvent>, PomError> {
+    let edate = checked(edate)? + 86400.0;
+    Ok(phases_from_with(Meeus, checked(sdate)? - 86400.0)?
+        .take_while(|event| event.time < edate)
+        .filter(|event| event.kind == kind)
+        .collect())
+}
+
+// SolarEclipseKind - what the Moon does to the Sun at greatest eclipse.
+// A hybrid eclipse is annular at the ends of its path and total in the
+// middle.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolarEclipseKind {
+    Partial,
+    Annular,
+    Total,
+    Hybrid,
+}
+
+impl std::fmt::Display for SolarEclipseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolarEclipseKind::Partial => write!(f, "Partial"),
+            SolarEclipseKind::Annular => write!(f, "Annular"),
+            SolarEclipseKind::Total => write!(f, "Total"),
+            SolarEclipseKind::Hybrid => write!(f, "Hybrid"),
+        }
+    }
+}
+
+// SolarEclipse - one eclipse of the Sun, seen from wherever on the Earth
+// sees the most of it.  A total or annular eclipse is central when the
+// axis of the shadow touches the Earth, and not when only the edge of the
+// shadow grazes a polar region.  The magnitude is the fraction of the
+// Sun's diameter covered at greatest eclipse (over 1 when total).
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarEclipse<T = f64> {
+    pub kind: SolarEclipseKind,
+    pub central: bool,
+    pub greatest: T,
+    pub gamma: f64,
+    pub magnitude: f64,
+    pub lunation: i64,
+}
+
+impl SolarEclipse {
+    // with_timezone - the same eclipse with its time as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<SolarEclipse<DateTime<Tz>>, PomError> {
+        Ok(SolarEclipse {
+            kind: self.kind,
+            central: self.central,
+            greatest: datetime(self.greatest, tz)?,
+            gamma: self.gamma,
+            magnitude: self.magnitude,
+            lunation: self.lunation,
+        })
+    }
+}
+
+// solar_eclipse - the eclipse at a new moon, if there is one.  Any other
+// phase has none.
+
+pub fn solar_eclipse(event: &PhaseEvent) -> Result<Option<SolarEclipse>, PomError> {
+    checked(event.time)?;
+    if event.kind != PhaseKind::New {
+        return Ok(None);
+    }
+    let s = match syzygy(event.lunation as f64, 0.0) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let g = s.gamma.abs();
+    if g > 1.5433 + s.u {
+        return Ok(None);
+    }
+
+    // the umbra narrows (and the penumbra widens) by omega between the
+    // fundamental plane and the ground under the axis of the shadow
+    let omega = 0.00464 * (1.0 - g * g).max(0.0).sqrt();
+    let central = g < 0.9972;
+    let (kind, magnitude) = if g < 0.9972 + s.u.abs() {
+        let kind = if s.u < 0.0 {
+            SolarEclipseKind::Total
+        } else if s.u > 0.0047 || !central || s.u >= omega {
+            SolarEclipseKind::Annular
+        } else {
+            SolarEclipseKind::Hybrid
+        };
+        (kind, (0.5461 + 2.0 * omega) / (0.5461 + 2.0 * s.u))
+    } else {
+        (
+            SolarEclipseKind::Partial,
+            (1.5433 + s.u - g) / (0.5461 + 2.0 * s.u),
+        )
+    };
+
+    Ok(Some(SolarEclipse {
+        kind,
+        central,
+        greatest: seconds(s.jde),
+        gamma: s.gamma,
+        magnitude,
+        lunation: event.lunation,
+    }))
+}
+
+// solar_eclipses - every solar eclipse whose greatest eclipse falls on or
+// after sdate but before edate (seconds since 1970)
+
+pub fn solar_eclipses(sdate: f64, edate: f64) -> Result<Vec<SolarEclipse>, PomError> {
+    let mut eclipses = Vec::new();
+    for event in candidates(sdate, edate, PhaseKind::New)? {
+        if let Some(eclipse) = solar_eclipse(&event)? {
+            if eclipse.greatest >= sdate && eclipse.greatest < edate {
+                eclipses.push(eclipse);
+            }
+        }
+    }
+    Ok(eclipses)
+}
@@ -0,0 +1,218 @@
+/*
+  Elongation - how far the Moon has moved round from the Sun, 0 at new
+  moon, 90 at first quarter, 180 at full and 270 at last quarter: the
+  difference of their ecliptic longitudes, as the position module has
+  them.  phase() reports the Moon's age from its longitude in its orbit
+  instead, which strays from this by up to a tenth of a degree or so
+  (a dozen minutes of time), so times found here can differ from
+  phase() by about that much.
+
+  Times at which it reaches a given angle are found by sampling and
+  bisection, as for rising and setting: the elongation only ever
+  increases, so each angle comes round once a lunation.
+
+  The illuminated fraction is (1 - cos(age)) / 2, and its crossings are
+  found the same way on the age phase() uses, so that they agree with
+  phase().  Each fraction comes round twice, once waxing and once waning.
+*/
+
+use super::riseset::crossings;
+use super::{checked, datetime, fixangle, jtime, sunmoon, timescale, PomError};
+use chrono::{DateTime, TimeZone};
+
+// elongation - the Moon's elongation east of the Sun, degrees, at t
+// (seconds since 1970): the Moon's ecliptic longitude less the Sun's
+
+pub fn elongation(t: f64) -> Result<f64, PomError> {
+    let sm = sunmoon(timescale::utc_to_tt(jtime(checked(t)?)))?;
+    Ok(fixangle(sm.lambdamoon - sm.lambdasun))
+}
+
+// age - the Moon's age as phase() has it, degrees, at t (seconds since
+// 1970): its longitude in its orbit less the Sun's
+
+fn age(t: f64) -> Result<f64, PomError> {
+    let sm = sunmoon(timescale::utc_to_tt(jtime(checked(t)?)))?;
+    Ok(fixangle(sm.lpp - sm.lambdasun))
+}
+
+// elongation_times - every time on or after sdate but before edate
+// (seconds since 1970) at which the elongation reaches angle (degrees)
+
+pub fn elongation_times(angle: f64, sdate: f64, edate: f64) -> Result<Vec<f64>, PomError> {
+    reaches(elongation, angle, sdate, edate)
+}
+
+// reaches - every time on or after sdate but before edate at which f, an
+// angle that only ever increases, reaches angle
+
+fn reaches(
+    f: impl Fn(f64) -> Result<f64, PomError>,
+    angle: f64,
+    sdate: f64,
+    edate: f64,
+) -> Result<Vec<f64>, PomError> {
+    if !angle.is_finite() {
+        return Err(PomError::InvalidPhase(angle));
+    }
+    let angle = fixangle(angle);
+    let sdate = checked(sdate)?;
+    let edate = checked(edate)?;
+    if edate <= sdate {
+        return Ok(Vec::new());
+    }
+
+    // f less angle, in -180 to +180: it rises through 0 when the angle is
+    // reached and drops back from +180 to -180 half a month later, which
+    // is no crossing
+    let past = |t: f64| -> Result<f64, PomError> {
+        let d = f(t)? - angle;
+        Ok(d - 360.0 * ((d + 180.0) / 360.0).floor())
+    };
+    Ok(crossings(sdate, edate, past)?
+        .into_iter()
+        .filter(|(_, rising)| *rising)
+        .map(|(t, _)| t)
+        .collect())
+}
+
+// Octant - the midpoints between the principal phases
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Octant {
+    WaxingCrescent,
+    WaxingGibbous,
+    WaningGibbous,
+    WaningCrescent,
+}
+
+impl Octant {
+    pub const ALL: [Octant; 4] = [
+        Octant::WaxingCrescent,
+        Octant::WaxingGibbous,
+        Octant::WaningGibbous,
+        Octant::WaningCrescent,
+    ];
+
+    // elongation - the Moon's elongation at this octant, degrees
+    pub fn elongation(self) -> f64 {
+        match self {
+            Octant::WaxingCrescent => 45.0,
+            Octant::WaxingGibbous => 135.0,
+            Octant::WaningGibbous => 225.0,
+            Octant::WaningCrescent => 315.0,
+        }
+    }
+}
+
+impl std::fmt::Display for Octant {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Octant::WaxingCrescent => write!(f, "Waxing crescent"),
+            Octant::WaxingGibbous => write!(f, "Waxing gibbous"),
+            Octant::WaningGibbous => write!(f, "Waning gibbous"),
+            Octant::WaningCrescent => write!(f, "Waning crescent"),
+        }
+    }
+}
+
+// OctantEvent - one octant, and when it happens (seconds since 1970,
+// unless mapped into a chrono DateTime)
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OctantEvent<T = f64> {
+    pub octant: Octant,
+    pub time: T,
+}
+
+impl OctantEvent {
+    // with_timezone - the same event as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<OctantEvent<DateTime<Tz>>, PomError> {
+        Ok(OctantEvent {
+            octant: self.octant,
+            time: datetime(self.time, tz)?,
+        })
+    }
+}
+
+impl<T> OctantEvent<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> OctantEvent<U> {
+        OctantEvent {
+            octant: self.octant,
+            time: f(self.time),
+        }
+    }
+}
+
+// octants - every octant on or after sdate but before edate (seconds
+// since 1970), in ascending order of time
+
+pub fn octants(sdate: f64, edate: f64) -> Result<Vec<OctantEvent>, PomError> {
+    let mut events = Vec::new();
+    for octant in Octant::ALL {
+        for time in elongation_times(octant.elongation(), sdate, edate)? {
+            events.push(OctantEvent { octant, time });
+        }
+    }
+    events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(events)
+}
+
+// IlluminationCrossing - a moment the illuminated fraction passes a
+// given value, on the way up (waxing) or down
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IlluminationCrossing<T = f64> {
+    pub time: T,
+    pub waxing: bool,
+}
+
+impl IlluminationCrossing {
+    // with_timezone - the same crossing as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<IlluminationCrossing<DateTime<Tz>>, PomError> {
+        Ok(IlluminationCrossing {
+            time: datetime(self.time, tz)?,
+            waxing: self.waxing,
+        })
+    }
+}
+
+impl<T> IlluminationCrossing<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> IlluminationCrossing<U> {
+        IlluminationCrossing {
+            time: f(self.time),
+            waxing: self.waxing,
+        }
+    }
+}
+
+// illumination_crossings - every time on or after sdate but before edate
+// (seconds since 1970) at which phase()'s illuminated fraction of the
+// Moon's disc passes fraction (0 to 1, exclusive), in ascending order of
+// time
+
+pub fn illumination_crossings(
+    fraction: f64,
+    sdate: f64,
+    edate: f64,
+) -> Result<Vec<IlluminationCrossing>, PomError> {
+    if !(fraction > 0.0 && fraction < 1.0) {
+        return Err(PomError::InvalidFraction(fraction));
+    }
+    let angle = (1.0 - 2.0 * fraction).acos().to_degrees();
+
+    let mut found = Vec::new();
+    for (age_at, waxing) in [(angle, true), (360.0 - angle, false)] {
+        for time in reaches(age, age_at, sdate, edate)? {
+            found.push(IlluminationCrossing { time, waxing });
+        }
+    }
+    found.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(found)
+}
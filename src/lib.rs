@@ -2,46 +2,74 @@
   A direct port of the perl module Astro::MoonPhase;
 */
 
-use chrono::{offset::FixedOffset, Local, Utc};
+pub mod apsides;
+pub mod bluemoon;
+pub mod easter;
+pub mod eclipse;
+pub mod elongation;
+pub mod meeus;
+mod model;
+pub mod names;
+pub mod observer;
+pub mod position;
+pub mod riseset;
+pub mod seasons;
+pub mod timescale;
+
+pub use model::{DuffettSmith, Meeus, PhaseModel};
+
+use chrono::{offset::FixedOffset, DateTime, Local, TimeZone, Utc};
+
+// PomError - everything that can go wrong in here
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PomError {
+    InvalidPhase(f64),    // phase selector other than 0.0, 0.25, 0.5 or 0.75
+    TimeOutOfRange(f64),  // seconds since 1970 which are NaN, infinite or beyond chrono
+    InvalidOffset(i32),   // time zone offset in hours east of UTC
+    NoConvergence,        // an iterative solution failed to settle
+    InvalidLocation,      // latitude beyond the poles, or a NaN anywhere in an Observer
+    InvalidYear(i32),     // a year the calculation does not cover
+    InvalidFraction(f64), // illuminated fraction not strictly between 0 and 1
+}
+
+impl std::fmt::Display for PomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PomError::InvalidPhase(phase) => write!(f, "invalid phase selector ({})", phase),
+            PomError::TimeOutOfRange(t) => write!(f, "time out of range ({})", t),
+            PomError::InvalidOffset(tz) => write!(f, "invalid time zone offset ({} hours)", tz),
+            PomError::NoConvergence => f.write_str("calculation did not converge"),
+            PomError::InvalidLocation => f.write_str("invalid observer location"),
+            PomError::InvalidYear(year) => write!(f, "year out of range ({})", year),
+            PomError::InvalidFraction(x) => write!(f, "invalid illuminated fraction ({})", x),
+        }
+    }
+}
+
+impl std::error::Error for PomError {}
 
 // Astronomical constants.
 
-/*
-#[allow(dead_code)]
 const EPOCH: f64 = 2444238.5; // 1980 january 0.0
 
 // Constants defining the Sun's apparent orbit.
 
-#[allow(dead_code)]
 const ELONGE: f64 = 278.833540; // ecliptic longitude of the Sun at epoch 1980.0
-#[allow(dead_code)]
 const ELONGP: f64 = 282.596403; // ecliptic longitude of the Sun at perigee
-#[allow(dead_code)]
 const ECCENT: f64 = 0.016718; // eccentricity of Earth's orbit
-#[allow(dead_code)]
 const SUNSMAX: f64 = 1.495985e8; // semi-major axis of Earth's orbit, km
-#[allow(dead_code)]
 const SUNANGSIZ: f64 = 0.533128; // sun's angular size, degrees, at semi-major axis distance
 
 // Elements of the Moon's orbit, epoch 1980.0.
 
-#[allow(dead_code)]
 const MMLONG: f64 = 64.975464; // moon's mean longitude at the epoch
-#[allow(dead_code)]
 const MMLONGP: f64 = 349.383063; // mean longitude of the perigee at the epoch
-#[allow(dead_code)]
 const MLNODE: f64 = 151.950429; // mean longitude of the node at the epoch
-#[allow(dead_code)]
 const MINC: f64 = 5.145396; // inclination of the Moon's orbit
-#[allow(dead_code)]
 const MECC: f64 = 0.054900; // eccentricity of the Moon's orbit
-#[allow(dead_code)]
 const MANGSIZ: f64 = 0.5181; // moon's angular size at distance a from Earth
-#[allow(dead_code)]
 const MSMAX: f64 = 384401.0; // semi-major axis of Moon's orbit in km
-#[allow(dead_code)]
-const MPARALLAX: f64 = 0.9507; // parallax at distance a from Earth
-*/
 
 const SYNMONTH: f64 = 29.53058868; // synodic month (new Moon to new Moon)
 
@@ -50,12 +78,23 @@ const SYNMONTH: f64 = 29.53058868; // synodic month (new Moon to new Moon)
 //static PI: f64 = 3.141_592_653_589_793; // assume not near black hole nor in Tennessee
 static PI: f64 = std::f64::consts::PI; // assume not near black hole nor in Tennessee
 
+const EARTH_RADIUS: f64 = 6378.14; // equatorial radius, km
+const EARTH_FLAT: f64 = 0.99664719; // polar radius over equatorial radius
+
 // Handy mathematical functions.
 
+fn fixangle(a: f64) -> f64 {
+    a - 360.0 * (a / 360.0).floor()
+} // fix angle
+
 fn torad(d: f64) -> f64 {
     d * (PI / 180.0)
 } // deg->rad
 
+fn todeg(r: f64) -> f64 {
+    r * (180.0 / PI)
+} // rad->deg
+
 fn dsin(d: f64) -> f64 {
     torad(d).sin()
 } // sin from deg
@@ -79,6 +118,33 @@ fn jdaytosecs(jday: f64) -> f64 {
     (jday - 2440587.5) * 86400.0 // (juliandate - jdate of unix epoch)*(seconds per julian day)
 }
 
+// timestamp - convert a DateTime to seconds since 1970, keeping the
+// fraction of a second
+
+fn timestamp<Tz: TimeZone>(dt: &DateTime<Tz>) -> f64 {
+    dt.timestamp() as f64 + dt.timestamp_subsec_nanos() as f64 / 1e9
+}
+
+// datetime - convert seconds since 1970 (and fraction) to a DateTime in tz
+
+fn datetime<Tz: TimeZone>(t: f64, tz: &Tz) -> Result<DateTime<Tz>, PomError> {
+    let secs = checked(t)?.floor();
+    let nanos = ((t - secs) * 1e9).round().min(999_999_999.0);
+    DateTime::from_timestamp(secs as i64, nanos as u32)
+        .map(|dt| dt.with_timezone(tz))
+        .ok_or(PomError::TimeOutOfRange(t))
+}
+
+// checked - refuse seconds since 1970 which no calendar can hold
+
+fn checked(t: f64) -> Result<f64, PomError> {
+    if t.is_finite() {
+        Ok(t)
+    } else {
+        Err(PomError::TimeOutOfRange(t))
+    }
+}
+
 // jyear - convert Julian date to year, month, day, which are
 // returned via integer pointers to integers
 fn jyear(td: f64, yy: &mut f64, mm: &mut f64, dd: &mut f64) {
@@ -111,6 +177,217 @@ fn jyear(td: f64, yy: &mut f64, mm: &mut f64, dd: &mut f64) {
     };
 }
 
+// PhaseKind - the four principal phases, in the order they occur
+// within a lunation
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhaseKind {
+    New,
+    FirstQuarter,
+    Full,
+    LastQuarter,
+}
+
+impl PhaseKind {
+    pub const ALL: [PhaseKind; 4] = [
+        PhaseKind::New,
+        PhaseKind::FirstQuarter,
+        PhaseKind::Full,
+        PhaseKind::LastQuarter,
+    ];
+
+    // selector - the fraction of a lunation truephase() expects for this phase
+    pub fn selector(self) -> f64 {
+        match self {
+            PhaseKind::New => 0.0,
+            PhaseKind::FirstQuarter => 0.25,
+            PhaseKind::Full => 0.5,
+            PhaseKind::LastQuarter => 0.75,
+        }
+    }
+}
+
+impl std::fmt::Display for PhaseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            PhaseKind::New => "New Moon",
+            PhaseKind::FirstQuarter => "First quarter",
+            PhaseKind::Full => "Full Moon",
+            PhaseKind::LastQuarter => "Last quarter",
+        })
+    }
+}
+
+// LunationNumbering - the published ways of counting lunations.  Each
+// is a fixed offset from Meeus' count, whose lunation 0 began with the
+// new moon of 2000 January 6:
+//
+//   DuffettSmith  k used internally, lunation 0 began 1900 January 1
+//   Brown         lunation 1 began 1923 January 17
+//   Meeus         lunation 0 began 2000 January 6
+//   Goldstine     counts from 1001 BC January, as in Goldstine's tables
+//   Hebrew        counts from the molad of Tishrei AM 1 (3761 BC)
+//   Islamic       lunation 1 is Muharram AH 1 (AD 622)
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LunationNumbering {
+    DuffettSmith,
+    Brown,
+    Meeus,
+    Goldstine,
+    Hebrew,
+    Islamic,
+}
+
+impl LunationNumbering {
+    // offset - this count less Meeus' count
+    fn offset(self) -> i64 {
+        match self {
+            LunationNumbering::DuffettSmith => 1237,
+            LunationNumbering::Brown => 953,
+            LunationNumbering::Meeus => 0,
+            LunationNumbering::Goldstine => 37105,
+            LunationNumbering::Hebrew => 71234,
+            LunationNumbering::Islamic => 17038,
+        }
+    }
+
+    // convert - lunation n in this numbering, renumbered in another
+    pub fn convert(self, n: i64, to: LunationNumbering) -> i64 {
+        n - self.offset() + to.offset()
+    }
+}
+
+// PhaseEvent - one phase of the moon: which phase, when it happens
+// (seconds since 1970, unless mapped into a chrono DateTime) and the
+// lunation it belongs to.  The lunation is the synodic month index k
+// counted from the new moon of 1900 January (LunationNumbering::DuffettSmith);
+// quarters and the full moon share the k of the new moon which starts
+// their lunation.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseEvent<T = f64> {
+    pub kind: PhaseKind,
+    pub time: T,
+    pub lunation: i64,
+}
+
+impl PhaseEvent {
+    fn new<M: PhaseModel>(model: &M, k: f64, kind: PhaseKind) -> Result<PhaseEvent, PomError> {
+        Ok(PhaseEvent {
+            kind,
+            time: jdaytosecs(timescale::tt_to_utc(model.true_phase(k, kind.selector())?)),
+            lunation: k as i64,
+        })
+    }
+
+    // with_timezone - the same event as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<PhaseEvent<DateTime<Tz>>, PomError> {
+        Ok(PhaseEvent {
+            kind: self.kind,
+            time: datetime(self.time, tz)?,
+            lunation: self.lunation,
+        })
+    }
+}
+
+impl<T> PhaseEvent<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> PhaseEvent<U> {
+        PhaseEvent {
+            kind: self.kind,
+            time: f(self.time),
+            lunation: self.lunation,
+        }
+    }
+
+    // lunation_number - the lunation this event belongs to, as numbered by
+    // the given scheme
+    pub fn lunation_number(&self, numbering: LunationNumbering) -> i64 {
+        LunationNumbering::DuffettSmith.convert(self.lunation, numbering)
+    }
+}
+
+// Lunation - the five phases which bound one lunation, starting and
+// ending with its new moons
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lunation<T = f64> {
+    events: [PhaseEvent<T>; 5],
+}
+
+impl Lunation {
+    fn new<M: PhaseModel>(model: &M, k: f64) -> Result<Lunation, PomError> {
+        Ok(Lunation {
+            events: [
+                PhaseEvent::new(model, k, PhaseKind::New)?,
+                PhaseEvent::new(model, k, PhaseKind::FirstQuarter)?,
+                PhaseEvent::new(model, k, PhaseKind::Full)?,
+                PhaseEvent::new(model, k, PhaseKind::LastQuarter)?,
+                PhaseEvent::new(model, k + 1.0, PhaseKind::New)?,
+            ],
+        })
+    }
+
+    // with_timezone - the same lunation with every event as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> Result<Lunation<DateTime<Tz>>, PomError> {
+        let [nm, fq, fm, lq, nnm] = &self.events;
+        Ok(Lunation {
+            events: [
+                nm.with_timezone(tz)?,
+                fq.with_timezone(tz)?,
+                fm.with_timezone(tz)?,
+                lq.with_timezone(tz)?,
+                nnm.with_timezone(tz)?,
+            ],
+        })
+    }
+}
+
+impl<T> Lunation<T> {
+    pub fn number(&self) -> i64 {
+        self.events[0].lunation
+    }
+
+    // lunation_number - number() as counted by the given scheme
+    pub fn lunation_number(&self, numbering: LunationNumbering) -> i64 {
+        self.events[0].lunation_number(numbering)
+    }
+
+    pub fn new_moon(&self) -> &PhaseEvent<T> {
+        &self.events[0]
+    }
+
+    pub fn first_quarter(&self) -> &PhaseEvent<T> {
+        &self.events[1]
+    }
+
+    pub fn full_moon(&self) -> &PhaseEvent<T> {
+        &self.events[2]
+    }
+
+    pub fn last_quarter(&self) -> &PhaseEvent<T> {
+        &self.events[3]
+    }
+
+    pub fn next_new_moon(&self) -> &PhaseEvent<T> {
+        &self.events[4]
+    }
+
+    // events - all five phases, in order of time
+    pub fn events(&self) -> &[PhaseEvent<T>; 5] {
+        &self.events
+    }
+
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Lunation<U> {
+        Lunation {
+            events: self.events.map(|e| e.map(&mut f)),
+        }
+    }
+}
+
 /*  meanphase  --  Calculates  time  of  the mean new Moon for a given
                    base date.  This argument K to this function is the
                    precomputed synodic month index, given by:
@@ -133,11 +410,10 @@ fn meanphase(sdate: f64, k: f64) -> f64 {
 
 // truephase - given a K value used to determine the mean phase of the
 // new moon, and a phase selector (0.0, 0.25, 0.5, 0.75),
-// obtain the true, corrected phase time
-
-fn truephase(k: f64, phase: f64) -> f64 {
-    let mut apcor = 0.0;
+// obtain the true, corrected phase time (see meeus::truephase for
+// the more accurate series)
 
+pub fn truephase(k: f64, phase: f64) -> Result<f64, PomError> {
     let k = k + phase; // add phase to new moon time
     let t = k / 1236.85; // time in Julian centuries from
                          // 1900 January 0.5
@@ -171,7 +447,6 @@ fn truephase(k: f64, phase: f64) -> f64 {
             - 0.0006 * dsin(2.0 * f + mprime)
             + 0.0010 * dsin(2.0 * f - mprime)
             + 0.0005 * dsin(m + 2.0 * mprime);
-        apcor = 1.0;
     } else if (phase - 0.25).abs() < 0.01 || (phase - 0.75).abs() < 0.01 {
         pt += (0.1721 - 0.0004 * t) * dsin(m) + 0.0021 * dsin(2.0 * m) - 0.6280 * dsin(mprime)
             + 0.0089 * dsin(2.0 * mprime)
@@ -193,36 +468,55 @@ fn truephase(k: f64, phase: f64) -> f64 {
             // Last quarter correction.
             pt += -0.0028 + 0.0004 * dcos(m) - 0.0003 * dcos(mprime);
         }
-        apcor = 1.0;
+    } else {
+        return Err(PomError::InvalidPhase(phase));
     }
-    //asset!(apcor, format!("truephase() called with invalid phase selector ({}).\n", phase);
-    assert_eq!(
-        apcor, 1.0,
-        "truephase() called with invalid phase selector ({}).\n",
-        phase
-    );
-    pt
+    Ok(pt)
 }
 
 // phasehunt - find time of phases of the moon which surround the current
 // date.  Five phases are found, starting and ending with the
 // new moons which bound the current lunation
 
-//fn phasehunt<Tz: chrono::TimeZone>(sdate: Option<DateTime<Tz>>) -> Vec<f64> {
-pub fn phasehunt(sdate: Option<f64>, tz: Option<i32>) -> Vec<f64> {
+pub fn phasehunt(sdate: Option<f64>, tz: Option<i32>) -> Result<Lunation, PomError> {
     let sdate: f64 = match sdate {
         None => match tz {
-            None => jtime(Local::now().timestamp() as f64),
-            Some(tmz) => jtime(
-                Utc::now()
-                    .with_timezone(&{ FixedOffset::east_opt(tmz * 60i32 * 60i32).unwrap() })
-                    .timestamp() as f64,
-            ),
+            None => Local::now().timestamp() as f64,
+            Some(tmz) => Utc::now()
+                .with_timezone(
+                    &tmz.checked_mul(60i32 * 60i32)
+                        .and_then(FixedOffset::east_opt)
+                        .ok_or(PomError::InvalidOffset(tmz))?,
+                )
+                .timestamp() as f64,
         },
-        Some(_) => jtime(sdate.expect("Try converting to a timestamp")),
+        Some(t) => t,
     };
 
-    let mut adate = sdate - 45.0;
+    phasehunt_with(DuffettSmith, sdate)
+}
+
+// phasehunt_with - phasehunt for the given date (seconds since 1970),
+// with the phase times worked out by model
+
+pub fn phasehunt_with<M: PhaseModel>(model: M, sdate: f64) -> Result<Lunation, PomError> {
+    let k = lunation_k(&model, timescale::utc_to_tt(jtime(checked(sdate)?)))?;
+    Lunation::new(&model, k)
+}
+
+// phasehunt_tz - phasehunt for a chrono DateTime in any time zone; the
+// events come back in that same zone, to the fraction of a second
+
+pub fn phasehunt_tz<Tz: TimeZone>(
+    sdate: &DateTime<Tz>,
+) -> Result<Lunation<DateTime<Tz>>, PomError> {
+    phasehunt(Some(timestamp(sdate)), None)?.with_timezone(&sdate.timezone())
+}
+
+// lunation_k - the k of the lunation in progress at Julian date sdate (TT)
+
+fn lunation_k<M: PhaseModel>(model: &M, sdate: f64) -> Result<f64, PomError> {
+    let adate = sdate - 45.0;
     let mut yy = 0.0;
     let mut mm = 0.0;
     let mut dd = 0.0;
@@ -230,239 +524,355 @@ pub fn phasehunt(sdate: Option<f64>, tz: Option<i32>) -> Vec<f64> {
     jyear(adate, &mut yy, &mut mm, &mut dd);
     let mut k1: f64 = ((yy + ((mm - 1.0) * (1.0 / 12.0)) - 1900.0) * 12.3685).floor();
 
-    let nt1 = meanphase(adate, k1);
-    adate = nt1;
-
     let mut nt1 = 0.0;
-    let mut k2: f64;
 
-    loop {
-        adate += SYNMONTH;
-        k2 = k1 + 1.0;
-        let nt2 = meanphase(adate, k2);
+    // the first guess is a couple of months early at worst
+    for _ in 0..12 {
+        let k2 = k1 + 1.0;
+        let nt2 = model.mean_phase(k2);
         if nt1 <= sdate && nt2 > sdate {
-            break;
+            return Ok(k1);
         }
         nt1 = nt2;
         k1 = k2;
     }
 
-    Vec::from([
-        jdaytosecs(truephase(k1, 0.0)),
-        jdaytosecs(truephase(k1, 0.25)),
-        jdaytosecs(truephase(k1, 0.5)),
-        jdaytosecs(truephase(k1, 0.75)),
-        jdaytosecs(truephase(k2, 0.0)),
-    ])
+    Err(PomError::NoConvergence)
 }
 
-/*
-package Astro::MoonPhase;
-@EXPORT = qw(phase phasehunt phaselist);
-$VERSION = '0.60';
+// phaselist - find time of phases of the moon between two dates
+// times (in & out) are seconds_since_1970
+//
+// Every phase on or after sdate but before edate is returned, in
+// ascending order of time.
 
-# Handy mathematical functions.
+pub fn phaselist(sdate: f64, edate: f64) -> Result<Vec<PhaseEvent>, PomError> {
+    phaselist_with(DuffettSmith, sdate, edate)
+}
 
-sub sgn		{ return (($_[0] < 0) ? -1 : ($_[0] > 0 ? 1 : 0)); } 	# extract sign
-sub fixangle	{ return ($_[0] - 360.0 * (floor($_[0] / 360.0))); }	# fix angle
-sub torad	{ return ($_[0] * ($Pi / 180.0)); }						# deg->rad
-sub todeg	{ return ($_[0] * (180.0 / $Pi)); }						# rad->deg
-sub dsin	{ return (sin(torad($_[0]))); }						# sin from deg
-sub dcos	{ return (cos(torad($_[0]))); }						# cos from deg
+// phaselist_with - phaselist with the phase times worked out by model
 
-sub tan		{ return sin($_[0])/cos($_[0]); }
-sub asin	{ return ($_[0]<-1 or $_[0]>1) ? undef : atan2($_[0],sqrt(1-$_[0]*$_[0])); }
-sub atan {
-    if		($_[0]==0)	{ return 0; }
-    elsif	($_[0]>0)	{ return atan2(sqrt(1+$_[0]*$_[0]),sqrt(1+1/($_[0]*$_[0]))); }
-    else 				{ return -atan2(sqrt(1+$_[0]*$_[0]),sqrt(1+1/($_[0]*$_[0]))); }
+pub fn phaselist_with<M: PhaseModel>(
+    model: M,
+    sdate: f64,
+    edate: f64,
+) -> Result<Vec<PhaseEvent>, PomError> {
+    let sdate = checked(sdate)?;
+    let edate = checked(edate)?;
+    let mut phases = Vec::new();
+    let mut yy = 0.0;
+    let mut mm = 0.0;
+    let mut dd = 0.0;
+
+    jyear(jtime(sdate), &mut yy, &mut mm, &mut dd);
+    let mut k: f64 = ((yy + ((mm - 1.0) * (1.0 / 12.0)) - 1900.0) * 12.3685).floor() - 2.0;
+
+    loop {
+        k += 1.0;
+        for kind in PhaseKind::ALL {
+            let event = PhaseEvent::new(&model, k, kind)?;
+
+            if event.time >= edate {
+                return Ok(phases);
+            }
+
+            if event.time >= sdate {
+                phases.push(event);
+            }
+        }
+    }
 }
 
-sub floor {
-  my $val   = shift;
-  my $neg   = $val < 0;
-  my $asint = int($val);
-  my $exact = $val == $asint;
+// Phases - an endless run of phases of the moon, one lunation after
+// another, either forward or backward in time
 
-  return ($exact ? $asint : $neg ? $asint - 1 : $asint);
+#[derive(Debug, Clone)]
+pub struct Phases<M = DuffettSmith> {
+    model: M,
+    k: f64,        // lunation of the next event
+    index: usize,  // and its place within the lunation (PhaseKind::ALL)
+    forward: bool, // which way we are walking
 }
 
-# phaselist - find time of phases of the moon between two dates
-# times (in & out) are seconds_since_1970
+impl<M: PhaseModel> Phases<M> {
+    fn peek(&self) -> Result<PhaseEvent, PomError> {
+        PhaseEvent::new(&self.model, self.k, PhaseKind::ALL[self.index])
+    }
 
-sub phaselist
-{
-  my ($sdate, $edate) = map { jtime($_) } @_;
+    fn step(&mut self) {
+        if self.forward {
+            if self.index == 3 {
+                self.k += 1.0;
+                self.index = 0;
+            } else {
+                self.index += 1;
+            }
+        } else if self.index == 0 {
+            self.k -= 1.0;
+            self.index = 3;
+        } else {
+            self.index -= 1;
+        }
+    }
+}
 
-  my (@phases, $d, $k, $yy, $mm);
+impl<M: PhaseModel> Iterator for Phases<M> {
+    type Item = PhaseEvent;
 
-  jyear($sdate, \$yy, \$mm, \$d);
-  $k = floor(($yy + (($mm - 1) * (1.0 / 12.0)) - 1900) * 12.3685) - 2;
+    fn next(&mut self) -> Option<PhaseEvent> {
+        let event = self.peek().ok()?;
+        self.step();
+        Some(event)
+    }
+}
 
-  while (1) {
-    ++$k;
-    for my $phase (0.0, 0.25, 0.5, 0.75) {
-      $d = truephase($k, $phase);
+// phases_from - every phase on or after t (seconds since 1970), in
+// ascending order of time, without end
 
-      return @phases if $d >= $edate;
+pub fn phases_from(t: f64) -> Result<Phases, PomError> {
+    phases_from_with(DuffettSmith, t)
+}
 
-      if ($d >= $sdate) {
-        push @phases, int(4 * $phase) unless @phases;
-        push @phases, jdaytosecs($d);
-      } # end if date should be listed
-    } # end for each $phase
-  } # end while 1
-} # end phaselist
+// phases_from_with - phases_from with the phase times worked out by model
 
+pub fn phases_from_with<M: PhaseModel>(model: M, t: f64) -> Result<Phases<M>, PomError> {
+    let k = lunation_k(&model, timescale::utc_to_tt(jtime(checked(t)?)))?;
+    let mut phases = Phases {
+        model,
+        k: k - 1.0,
+        index: 0,
+        forward: true,
+    };
+    while phases.peek()?.time < t {
+        phases.step();
+    }
+    Ok(phases)
+}
 
+// phases_before - every phase before t (seconds since 1970), in
+// descending order of time, without end
 
-# kepler - solve the equation of Kepler
+pub fn phases_before(t: f64) -> Result<Phases, PomError> {
+    phases_before_with(DuffettSmith, t)
+}
 
-sub kepler {
-    my ($m, $ecc) = @_;
-    my ($e, $delta);
-    my $EPSILON = 1e-6;
+// phases_before_with - phases_before with the phase times worked out by model
 
-    $m = torad($m);
-    $e = $m;
-    do {
-        $delta = $e - $ecc * sin($e) - $m;
-        $e -= $delta / (1 - $ecc * cos($e));
-    } while (abs($delta) > $EPSILON);
-    return ($e);
+pub fn phases_before_with<M: PhaseModel>(model: M, t: f64) -> Result<Phases<M>, PomError> {
+    let k = lunation_k(&model, timescale::utc_to_tt(jtime(checked(t)?)))?;
+    let mut phases = Phases {
+        model,
+        k: k + 1.0,
+        index: 3,
+        forward: false,
+    };
+    while phases.peek()?.time >= t {
+        phases.step();
+    }
+    Ok(phases)
 }
 
+// kepler - solve the equation of Kepler
 
+fn kepler(m: f64, ecc: f64) -> Result<f64, PomError> {
+    const EPSILON: f64 = 1e-6;
 
-# phase - calculate phase of moon as a fraction:
-#
-# The argument is the time for which the phase is requested,
-# expressed as a Julian date and fraction.  Returns the terminator
-# phase angle as a percentage of a full circle (i.e., 0 to 1),
-# and stores into pointer arguments the illuminated fraction of
-# the Moon's disc, the Moon's age in days and fraction, the
-# distance of the Moon from the centre of the Earth, and the
-# angular diameter subtended by the Moon as seen by an observer
-# at the centre of the Earth.
+    let m = torad(m);
+    let mut e = m;
+    for _ in 0..100 {
+        let delta = e - ecc * e.sin() - m;
+        e -= delta / (1.0 - ecc * e.cos());
+        if delta.abs() <= EPSILON {
+            return Ok(e);
+        }
+    }
+    Err(PomError::NoConvergence)
+}
 
-sub phase {
-    my $pdate = jtime(shift || time());
+// MoonPhase - everything phase() knows about the Moon (and Sun) at an instant
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonPhase {
+    pub phase: f64,    // terminator phase angle as a fraction of a full circle (0 to 1)
+    pub illum: f64,    // illuminated fraction of the Moon's disc
+    pub age: f64,      // Moon's age in days and fraction
+    pub dist: f64,     // distance of the Moon from the centre of the Earth, km
+    pub ang: f64,      // angular diameter of the Moon, degrees, seen from the centre of the Earth
+    pub sun_dist: f64, // distance to the Sun, km
+    pub sun_ang: f64,  // angular size of the Sun, degrees
+}
 
-    my $pphase;				# illuminated fraction
-    my $mage;				# age of moon in days
-    my $dist;				# distance in kilometres
-    my $angdia;				# angular diameter in degrees
-    my $sudist;				# distance to Sun
-    my $suangdia;				# sun's angular diameter
+// phase - calculate phase of moon as a fraction:
+//
+// The argument is the time for which the phase is requested,
+// expressed as seconds since 1970 (now, if omitted).  Returns the
+// terminator phase angle as a percentage of a full circle (i.e., 0 to 1),
+// the illuminated fraction of the Moon's disc, the Moon's age in days
+// and fraction, the distance of the Moon from the centre of the Earth,
+// and the angular diameter subtended by the Moon as seen by an observer
+// at the centre of the Earth, along with the distance and angular size
+// of the Sun.
+
+pub fn phase(t: Option<f64>) -> Result<MoonPhase, PomError> {
+    let pdate = timescale::utc_to_tt(jtime(checked(
+        t.unwrap_or_else(|| Utc::now().timestamp() as f64),
+    )?));
+    let sm = sunmoon(pdate)?;
+
+    // Calculation of the phase of the Moon.
+
+    // Age of the Moon in degrees.
+    let moon_age = sm.lpp - sm.lambdasun;
+
+    // Phase of the Moon.
+    let moon_phase = (1.0 - dcos(moon_age)) / 2.0;
+
+    Ok(MoonPhase {
+        phase: fixangle(moon_age) / 360.0,
+        illum: moon_phase,
+        age: SYNMONTH * (fixangle(moon_age) / 360.0),
+        dist: sm.moon_dist,
+        ang: sm.moon_ang,
+        sun_dist: sm.sun_dist,
+        sun_ang: sm.sun_ang,
+    })
+}
 
-    my ($Day, $N, $M, $Ec, $Lambdasun, $ml, $MM, $MN, $Ev, $Ae, $A3, $MmP,
-       $mEc, $A4, $lP, $V, $lPP, $NP, $y, $x, $Lambdamoon, $BetaM,
-       $MoonAge, $MoonPhase,
-       $MoonDist, $MoonDFrac, $MoonAng, $MoonPar,
-       $F, $SunDist, $SunAng,
-       $mpfrac);
+// SunMoon - where phase() finds the Sun and Moon; shared with the
+// position module
+
+struct SunMoon {
+    lambdasun: f64,  // Sun's geocentric ecliptic longitude, degrees
+    sun_dist: f64,   // distance to Sun in km
+    sun_ang: f64,    // Sun's angular size in degrees
+    lpp: f64,        // Moon's true longitude in its orbit, degrees
+    lambdamoon: f64, // Moon's geocentric ecliptic longitude, degrees
+    betam: f64,      // Moon's geocentric ecliptic latitude, degrees
+    moon_dist: f64,  // distance of the Moon from the centre of the Earth, km
+    moon_ang: f64,   // Moon's angular diameter in degrees
+}
 
-    # Calculation of the Sun's position.
+// sunmoon - positions of the Sun and Moon at Julian Ephemeris Day pdate
 
-    $Day = $pdate - $Epoch;						# date within epoch
-    $N = fixangle((360 / 365.2422) * $Day);				# mean anomaly of the Sun
-    $M = fixangle($N + $Elonge - $Elongp);				# convert from perigee
-                                    # co-ordinates to epoch 1980.0
-    $Ec = kepler($M, $Eccent);					# solve equation of Kepler
-    $Ec = sqrt((1 + $Eccent) / (1 - $Eccent)) * tan($Ec / 2);
-    $Ec = 2 * todeg(atan($Ec));					# true anomaly
-    $Lambdasun = fixangle($Ec + $Elongp);				# Sun's geocentric ecliptic
-                                    # longitude
-    # Orbital distance factor.
-    $F = ((1 + $Eccent * cos(torad($Ec))) / (1 - $Eccent * $Eccent));
-    $SunDist = $Sunsmax / $F;					# distance to Sun in km
-    $SunAng = $F * $Sunangsiz;					# Sun's angular size in degrees
+fn sunmoon(pdate: f64) -> Result<SunMoon, PomError> {
+    // Calculation of the Sun's position.
 
+    let day = pdate - EPOCH; // date within epoch
+    let n = fixangle((360.0 / 365.2422) * day); // mean anomaly of the Sun
+    let m = fixangle(n + ELONGE - ELONGP); // convert from perigee co-ordinates to epoch 1980.0
+    let ec = kepler(m, ECCENT)?; // solve equation of Kepler
+    let ec = ((1.0 + ECCENT) / (1.0 - ECCENT)).sqrt() * (ec / 2.0).tan();
+    let ec = 2.0 * todeg(ec.atan()); // true anomaly
+    let lambdasun = fixangle(ec + ELONGP); // Sun's geocentric ecliptic longitude
 
-    # Calculation of the Moon's position.
+    // Orbital distance factor.
+    let f = (1.0 + ECCENT * dcos(ec)) / (1.0 - ECCENT * ECCENT);
+    let sun_dist = SUNSMAX / f; // distance to Sun in km
+    let sun_ang = f * SUNANGSIZ; // Sun's angular size in degrees
 
-    # Moon's mean longitude.
-    $ml = fixangle(13.1763966 * $Day + $Mmlong);
+    // Calculation of the Moon's position.
 
-    # Moon's mean anomaly.
-    $MM = fixangle($ml - 0.1114041 * $Day - $Mmlongp);
+    // Moon's mean longitude.
+    let ml = fixangle(13.1763966 * day + MMLONG);
 
-    # Moon's ascending node mean longitude.
-    $MN = fixangle($Mlnode - 0.0529539 * $Day);
+    // Moon's mean anomaly.
+    let mm = fixangle(ml - 0.1114041 * day - MMLONGP);
 
-    # Evection.
-    $Ev = 1.2739 * sin(torad(2 * ($ml - $Lambdasun) - $MM));
+    // Moon's ascending node mean longitude.
+    let mn = fixangle(MLNODE - 0.0529539 * day);
 
-    # Annual equation.
-    $Ae = 0.1858 * sin(torad($M));
+    // Evection.
+    let ev = 1.2739 * dsin(2.0 * (ml - lambdasun) - mm);
 
-    # Correction term.
-    $A3 = 0.37 * sin(torad($M));
+    // Annual equation.
+    let ae = 0.1858 * dsin(m);
 
-    # Corrected anomaly.
-    $MmP = $MM + $Ev - $Ae - $A3;
+    // Correction term.
+    let a3 = 0.37 * dsin(m);
 
-    # Correction for the equation of the centre.
-    $mEc = 6.2886 * sin(torad($MmP));
+    // Corrected anomaly.
+    let mmp = mm + ev - ae - a3;
 
-    # Another correction term.
-    $A4 = 0.214 * sin(torad(2 * $MmP));
+    // Correction for the equation of the centre.
+    let mec = 6.2886 * dsin(mmp);
 
-    # Corrected longitude.
-    $lP = $ml + $Ev + $mEc - $Ae + $A4;
+    // Another correction term.
+    let a4 = 0.214 * dsin(2.0 * mmp);
 
-    # Variation.
-    $V = 0.6583 * sin(torad(2 * ($lP - $Lambdasun)));
+    // Corrected longitude.
+    let lp = ml + ev + mec - ae + a4;
 
-    # True longitude.
-    $lPP = $lP + $V;
+    // Variation.
+    let v = 0.6583 * dsin(2.0 * (lp - lambdasun));
 
-    # Corrected longitude of the node.
-    $NP = $MN - 0.16 * sin(torad($M));
+    // True longitude.
+    let lpp = lp + v;
 
-    # Y inclination coordinate.
-    $y = sin(torad($lPP - $NP)) * cos(torad($Minc));
+    // Corrected longitude of the node.
+    let np = mn - 0.16 * dsin(m);
 
-    # X inclination coordinate.
-    $x = cos(torad($lPP - $NP));
+    // Y inclination coordinate.
+    let y = dsin(lpp - np) * dcos(MINC);
 
-    # Ecliptic longitude.
-    $Lambdamoon = todeg(atan2($y, $x));
-    $Lambdamoon += $NP;
+    // X inclination coordinate.
+    let x = dcos(lpp - np);
 
-    # Ecliptic latitude.
-    $BetaM = todeg(asin(sin(torad($lPP - $NP)) * sin(torad($Minc))));
+    // Ecliptic longitude.
+    let lambdamoon = fixangle(todeg(y.atan2(x)) + np);
 
-    # Calculation of the phase of the Moon.
+    // Ecliptic latitude.
+    let betam = todeg((dsin(lpp - np) * dsin(MINC)).asin());
 
-    # Age of the Moon in degrees.
-    $MoonAge = $lPP - $Lambdasun;
+    // Calculate distance of moon from the centre of the Earth.
 
-    # Phase of the Moon.
-    $MoonPhase = (1 - cos(torad($MoonAge))) / 2;
+    let moon_dist = (MSMAX * (1.0 - MECC * MECC)) / (1.0 + MECC * dcos(mmp + mec));
 
-    # Calculate distance of moon from the centre of the Earth.
+    // Calculate Moon's angular diameter.
 
-    $MoonDist = ($Msmax * (1 - $Mecc * $Mecc)) /
-        (1 + $Mecc * cos(torad($MmP + $mEc)));
+    let moon_dfrac = moon_dist / MSMAX;
+    let moon_ang = MANGSIZ / moon_dfrac;
 
-    # Calculate Moon's angular diameter.
+    Ok(SunMoon {
+        lambdasun,
+        sun_dist,
+        sun_ang,
+        lpp,
+        lambdamoon,
+        betam,
+        moon_dist,
+        moon_ang,
+    })
+}
 
-    $MoonDFrac = $MoonDist / $Msmax;
-    $MoonAng = $Mangsiz / $MoonDFrac;
+/*
+package Astro::MoonPhase;
+@EXPORT = qw(phase phasehunt phaselist);
+$VERSION = '0.60';
 
-    # Calculate Moon's parallax.
+# Handy mathematical functions.
 
-    $MoonPar = $Mparallax / $MoonDFrac;
+sub sgn		{ return (($_[0] < 0) ? -1 : ($_[0] > 0 ? 1 : 0)); } 	# extract sign
+sub fixangle	{ return ($_[0] - 360.0 * (floor($_[0] / 360.0))); }	# fix angle
+sub torad	{ return ($_[0] * ($Pi / 180.0)); }						# deg->rad
+sub todeg	{ return ($_[0] * (180.0 / $Pi)); }						# rad->deg
+sub dsin	{ return (sin(torad($_[0]))); }						# sin from deg
+sub dcos	{ return (cos(torad($_[0]))); }						# cos from deg
 
-    $pphase = $MoonPhase;
-    $mage = $Synmonth * (fixangle($MoonAge) / 360.0);
-    $dist = $MoonDist;
-    $angdia = $MoonAng;
-    $sudist = $SunDist;
-    $suangdia = $SunAng;
-    $mpfrac = fixangle($MoonAge) / 360.0;
-    return wantarray ? ( $mpfrac, $pphase, $mage, $dist, $angdia, $sudist,$suangdia ) : $mpfrac;
+sub tan		{ return sin($_[0])/cos($_[0]); }
+sub asin	{ return ($_[0]<-1 or $_[0]>1) ? undef : atan2($_[0],sqrt(1-$_[0]*$_[0])); }
+sub atan {
+    if		($_[0]==0)	{ return 0; }
+    elsif	($_[0]>0)	{ return atan2(sqrt(1+$_[0]*$_[0]),sqrt(1+1/($_[0]*$_[0]))); }
+    else 				{ return -atan2(sqrt(1+$_[0]*$_[0]),sqrt(1+1/($_[0]*$_[0]))); }
+}
+
+sub floor {
+  my $val   = shift;
+  my $neg   = $val < 0;
+  my $asint = int($val);
+  my $exact = $val == $asint;
+
+  return ($exact ? $asint : $neg ? $asint - 1 : $asint);
 }
 
 1;
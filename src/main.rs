@@ -1,6 +1,7 @@
-//use chrono::{DateTime, Utc};
-use chrono::{DateTime, Local};
-use pom::phasehunt;
+use chrono::{DateTime, Datelike, Local};
+use pom::easter::{astronomical_easter, easter, Computus};
+use pom::names::{full_moon_name, Hemisphere};
+use pom::{phasehunt_tz, PhaseEvent};
 
 fn main() {
     /*
@@ -10,23 +11,91 @@ fn main() {
        both of which were a thin wrapper to the perl module Astro::MoonPhase;
     */
 
-    //let p: Vec<String> = phasehunt(Some(Utc::now().timestamp() as f64), None)
-    let dt = Local::now();
-    let offset = dt.offset().clone();
-    //let p: Vec<String> = phasehunt(None, Some(offset.local_minus_utc() as i32))
-    let p: Vec<String> = phasehunt(None, None)
-        .into_iter()
-        .map(|x| DateTime::from_timestamp(x as i64, 0).unwrap().naive_local())
-        .map(|y| {
-            DateTime::<Local>::from_naive_utc_and_offset(y, offset)
-                .format("%a %b %e %H:%M:%S %Y (%Z)")
-                .to_string()
-        })
-        .collect();
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("easter") {
+        let year = match args.get(2).map(|y| y.parse::<i32>()) {
+            None => Local::now().year(),
+            Some(Ok(year)) => year,
+            Some(Err(_)) => {
+                eprintln!("usage: pom [easter [YEAR]]");
+                std::process::exit(2);
+            }
+        };
+        print_easter(year);
+        return;
+    }
 
-    println!("New noon      = {}", p[0]);
-    println!("First quarter = {}", p[1]);
-    println!("Full Moon     = {}", p[2]);
-    println!("Last quarter  = {}", p[3]);
-    println!("New moon      = {}", p[4]);
+    let lunation = match phasehunt_tz(&Local::now()) {
+        Ok(lunation) => lunation,
+        Err(e) => {
+            eprintln!("pom: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let when = |e: &PhaseEvent<DateTime<Local>>| e.time.format("%a %b %e %H:%M:%S %Y (%Z)");
+    let name = match full_moon_name(lunation.full_moon(), Hemisphere::Northern) {
+        Ok(name) => name.map(|n| format!(", {}", n)).unwrap_or_default(),
+        Err(e) => {
+            eprintln!("pom: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("New noon      = {}", when(lunation.new_moon()));
+    println!("First quarter = {}", when(lunation.first_quarter()));
+    println!("Full Moon     = {}{}", when(lunation.full_moon()), name);
+    println!("Last quarter  = {}", when(lunation.last_quarter()));
+    println!("New moon      = {}", when(lunation.next_new_moon()));
+}
+
+// print_easter - Easter of year by both computuses, and from the sky.
+// Each is printed on its own, so that a year one computus does not cover
+// still shows the others.
+
+fn print_easter(year: i32) {
+    let day = |d: chrono::NaiveDate| d.format("%a %b %e %Y").to_string();
+    let mut failed = 0;
+    let mut show = |label: &str, line: Result<String, pom::PomError>| match line {
+        Ok(line) => println!("{:<22} = {}", label, line),
+        Err(e) => {
+            println!("{:<22} = {}", label, e);
+            failed += 1;
+        }
+    };
+
+    let western = easter(year, Computus::Gregorian);
+    show("Easter (Gregorian)", western.map(|e| day(e.sunday)));
+    show(
+        "Easter (Julian)",
+        easter(year, Computus::Julian).map(|e| day(e.sunday)),
+    );
+    let sky = astronomical_easter(year);
+    show(
+        "Easter (astronomical)",
+        sky.map(|sky| {
+            format!(
+                "{}{}",
+                day(sky.sunday),
+                if sky.disagrees {
+                    ", not the Gregorian date"
+                } else {
+                    ""
+                }
+            )
+        }),
+    );
+    show(
+        "Paschal full moon",
+        sky.and_then(|sky| sky.paschal_full_moon.with_timezone(&Local))
+            .map(|e| e.time.format("%a %b %e %H:%M:%S %Y (%Z)").to_string()),
+    );
+    if let Ok(western) = western {
+        show(
+            "Golden number, epact",
+            Ok(format!("{}, {}", western.golden_number, western.epact)),
+        );
+    }
+    if failed == 4 {
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,375 @@
+/*
+  Phases of the Moon after Jean Meeus, Astronomical Algorithms
+  (2nd ed., Willmann-Bell, 1998), chapter 49, and the places of the Moon
+  (chapter 47) and Sun (chapter 25).
+
+  Good to well under a minute over several centuries either side of 2000,
+  against the minutes of the Practical Astronomy series in truephase().
+  The Moon is good to about 10 arc seconds and a few km, where phase()
+  can be out by a couple of arc minutes and several thousand km; the Sun
+  to about 0.01 degree.  Like the position module, both are referred to
+  the mean equinox of date.  They serve the eclipses and apsides inside
+  the crate; the public positions are those of the position module.
+*/
+
+use super::position::Ecliptic;
+use super::{dcos, dsin, fixangle, PomError};
+
+// truephase - given a K value used to determine the mean phase of the
+// new moon, and a phase selector (0.0, 0.25, 0.5, 0.75), obtain the
+// true, corrected phase time as a Julian Ephemeris Day.
+//
+// K counts lunations from the new moon of 1900 January, exactly as for
+// the crate's own truephase(), so the two can be compared lunation for
+// lunation; Meeus' own k is K - 1237.
+
+pub fn truephase(k: f64, phase: f64) -> Result<f64, PomError> {
+    let k = k - 1237.0 + phase; // Meeus' k, 0 at the new moon of 2000 January 6
+    let t = k / 1236.85; // time in Julian centuries from 2000 January 1.5
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    // mean time of phase
+    let mut jde =
+        2451550.09766 + 29.530588861 * k + 0.00015437 * t2 - 0.000000150 * t3 + 0.00000000073 * t4;
+
+    // eccentricity of the Earth's orbit
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+    let e2 = e * e;
+
+    // Sun's mean anomaly
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t2 - 0.00000011 * t3;
+
+    // Moon's mean anomaly
+    let mp = 201.5643 + 385.81693528 * k + 0.0107582 * t2 + 0.00001238 * t3 - 0.000000058 * t4;
+
+    // Moon's argument of latitude
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t2 - 0.00000227 * t3 + 0.000000011 * t4;
+
+    // longitude of the ascending node of the lunar orbit
+    let om = 124.7746 - 1.56375588 * k + 0.0020672 * t2 + 0.00000215 * t3;
+
+    if phase < 0.01 || (phase - 0.5).abs() < 0.01 {
+        // Corrections for New and Full Moon.
+        let (c1, c2, c3, c4, c5, c6, c7) = if phase < 0.01 {
+            (
+                -0.40720, 0.17241, 0.01608, 0.01039, 0.00739, -0.00514, 0.00208,
+            )
+        } else {
+            (
+                -0.40614, 0.17302, 0.01614, 0.01043, 0.00734, -0.00515, 0.00209,
+            )
+        };
+
+        jde += c1 * dsin(mp)
+            + c2 * e * dsin(m)
+            + c3 * dsin(2.0 * mp)
+            + c4 * dsin(2.0 * f)
+            + c5 * e * dsin(mp - m)
+            + c6 * e * dsin(mp + m)
+            + c7 * e2 * dsin(2.0 * m)
+            - 0.00111 * dsin(mp - 2.0 * f)
+            - 0.00057 * dsin(mp + 2.0 * f)
+            + 0.00056 * e * dsin(2.0 * mp + m)
+            - 0.00042 * dsin(3.0 * mp)
+            + 0.00042 * e * dsin(m + 2.0 * f)
+            + 0.00038 * e * dsin(m - 2.0 * f)
+            - 0.00024 * e * dsin(2.0 * mp - m)
+            - 0.00017 * dsin(om)
+            - 0.00007 * dsin(mp + 2.0 * m)
+            + 0.00004 * dsin(2.0 * mp - 2.0 * f)
+            + 0.00004 * dsin(3.0 * m)
+            + 0.00003 * dsin(mp + m - 2.0 * f)
+            + 0.00003 * dsin(2.0 * mp + 2.0 * f)
+            - 0.00003 * dsin(mp + m + 2.0 * f)
+            + 0.00003 * dsin(mp - m + 2.0 * f)
+            - 0.00002 * dsin(mp - m - 2.0 * f)
+            - 0.00002 * dsin(3.0 * mp + m)
+            + 0.00002 * dsin(4.0 * mp);
+    } else if (phase - 0.25).abs() < 0.01 || (phase - 0.75).abs() < 0.01 {
+        jde += -0.62801 * dsin(mp) + 0.17172 * e * dsin(m) - 0.01183 * e * dsin(mp + m)
+            + 0.00862 * dsin(2.0 * mp)
+            + 0.00804 * dsin(2.0 * f)
+            + 0.00454 * e * dsin(mp - m)
+            + 0.00204 * e2 * dsin(2.0 * m)
+            - 0.00180 * dsin(mp - 2.0 * f)
+            - 0.00070 * dsin(mp + 2.0 * f)
+            - 0.00040 * dsin(3.0 * mp)
+            - 0.00034 * e * dsin(2.0 * mp - m)
+            + 0.00032 * e * dsin(m + 2.0 * f)
+            + 0.00032 * e * dsin(m - 2.0 * f)
+            - 0.00028 * e2 * dsin(mp + 2.0 * m)
+            + 0.00027 * e * dsin(2.0 * mp + m)
+            - 0.00017 * dsin(om)
+            - 0.00005 * dsin(mp - m - 2.0 * f)
+            + 0.00004 * dsin(2.0 * mp + 2.0 * f)
+            - 0.00004 * dsin(mp + m + 2.0 * f)
+            + 0.00004 * dsin(mp - 2.0 * m)
+            + 0.00003 * dsin(mp + m - 2.0 * f)
+            + 0.00003 * dsin(3.0 * m)
+            + 0.00002 * dsin(2.0 * mp - 2.0 * f)
+            + 0.00002 * dsin(mp - m + 2.0 * f)
+            - 0.00002 * dsin(3.0 * mp + m);
+
+        // W, the quarter correction.
+        let w = 0.00306 - 0.00038 * e * dcos(m) + 0.00026 * dcos(mp) - 0.00002 * dcos(mp - m)
+            + 0.00002 * dcos(mp + m)
+            + 0.00002 * dcos(2.0 * f);
+        if phase < 0.5 {
+            jde += w;
+        } else {
+            jde -= w;
+        }
+    } else {
+        return Err(PomError::InvalidPhase(phase));
+    }
+
+    // Additional corrections for all phases, from the planetary arguments.
+    let a = [
+        (0.000325, 299.77 + 0.107408 * k - 0.009173 * t2),
+        (0.000165, 251.88 + 0.016321 * k),
+        (0.000164, 251.83 + 26.651886 * k),
+        (0.000126, 349.42 + 36.412478 * k),
+        (0.000110, 84.66 + 18.206239 * k),
+        (0.000062, 141.74 + 53.303771 * k),
+        (0.000060, 207.14 + 2.453732 * k),
+        (0.000056, 154.84 + 7.306860 * k),
+        (0.000047, 34.52 + 27.261239 * k),
+        (0.000042, 207.19 + 0.121824 * k),
+        (0.000040, 291.34 + 1.844379 * k),
+        (0.000037, 161.72 + 24.198154 * k),
+        (0.000035, 239.56 + 25.513099 * k),
+        (0.000023, 331.55 + 3.592518 * k),
+    ];
+    jde += a.iter().map(|(c, arg)| c * dsin(*arg)).sum::<f64>();
+
+    Ok(jde)
+}
+
+// Periodic terms for the longitude and distance of the Moon (Meeus table
+// 47.A): the multiples of D, M, M' and F, then the coefficients in
+// millionths of a degree and in metres.
+
+const LR_TERMS: [(f64, f64, f64, f64, f64, f64); 60] = [
+    (0.0, 0.0, 1.0, 0.0, 6288774.0, -20905355.0),
+    (2.0, 0.0, -1.0, 0.0, 1274027.0, -3699111.0),
+    (2.0, 0.0, 0.0, 0.0, 658314.0, -2955968.0),
+    (0.0, 0.0, 2.0, 0.0, 213618.0, -569925.0),
+    (0.0, 1.0, 0.0, 0.0, -185116.0, 48888.0),
+    (0.0, 0.0, 0.0, 2.0, -114332.0, -3149.0),
+    (2.0, 0.0, -2.0, 0.0, 58793.0, 246158.0),
+    (2.0, -1.0, -1.0, 0.0, 57066.0, -152138.0),
+    (2.0, 0.0, 1.0, 0.0, 53322.0, -170733.0),
+    (2.0, -1.0, 0.0, 0.0, 45758.0, -204586.0),
+    (0.0, 1.0, -1.0, 0.0, -40923.0, -129620.0),
+    (1.0, 0.0, 0.0, 0.0, -34720.0, 108743.0),
+    (0.0, 1.0, 1.0, 0.0, -30383.0, 104755.0),
+    (2.0, 0.0, 0.0, -2.0, 15327.0, 10321.0),
+    (0.0, 0.0, 1.0, 2.0, -12528.0, 0.0),
+    (0.0, 0.0, 1.0, -2.0, 10980.0, 79661.0),
+    (4.0, 0.0, -1.0, 0.0, 10675.0, -34782.0),
+    (0.0, 0.0, 3.0, 0.0, 10034.0, -23210.0),
+    (4.0, 0.0, -2.0, 0.0, 8548.0, -21636.0),
+    (2.0, 1.0, -1.0, 0.0, -7888.0, 24208.0),
+    (2.0, 1.0, 0.0, 0.0, -6766.0, 30824.0),
+    (1.0, 0.0, -1.0, 0.0, -5163.0, -8379.0),
+    (1.0, 1.0, 0.0, 0.0, 4987.0, -16675.0),
+    (2.0, -1.0, 1.0, 0.0, 4036.0, -12831.0),
+    (2.0, 0.0, 2.0, 0.0, 3994.0, -10445.0),
+    (4.0, 0.0, 0.0, 0.0, 3861.0, -11650.0),
+    (2.0, 0.0, -3.0, 0.0, 3665.0, 14403.0),
+    (0.0, 1.0, -2.0, 0.0, -2689.0, -7003.0),
+    (2.0, 0.0, -1.0, 2.0, -2602.0, 0.0),
+    (2.0, -1.0, -2.0, 0.0, 2390.0, 10056.0),
+    (1.0, 0.0, 1.0, 0.0, -2348.0, 6322.0),
+    (2.0, -2.0, 0.0, 0.0, 2236.0, -9884.0),
+    (0.0, 1.0, 2.0, 0.0, -2120.0, 5751.0),
+    (0.0, 2.0, 0.0, 0.0, -2069.0, 0.0),
+    (2.0, -2.0, -1.0, 0.0, 2048.0, -4950.0),
+    (2.0, 0.0, 1.0, -2.0, -1773.0, 4130.0),
+    (2.0, 0.0, 0.0, 2.0, -1595.0, 0.0),
+    (4.0, -1.0, -1.0, 0.0, 1215.0, -3958.0),
+    (0.0, 0.0, 2.0, 2.0, -1110.0, 0.0),
+    (3.0, 0.0, -1.0, 0.0, -892.0, 3258.0),
+    (2.0, 1.0, 1.0, 0.0, -810.0, 2616.0),
+    (4.0, -1.0, -2.0, 0.0, 759.0, -1897.0),
+    (0.0, 2.0, -1.0, 0.0, -713.0, -2117.0),
+    (2.0, 2.0, -1.0, 0.0, -700.0, 2354.0),
+    (2.0, 1.0, -2.0, 0.0, 691.0, 0.0),
+    (2.0, -1.0, 0.0, -2.0, 596.0, 0.0),
+    (4.0, 0.0, 1.0, 0.0, 549.0, -1423.0),
+    (0.0, 0.0, 4.0, 0.0, 537.0, -1117.0),
+    (4.0, -1.0, 0.0, 0.0, 520.0, -1571.0),
+    (1.0, 0.0, -2.0, 0.0, -487.0, -1739.0),
+    (2.0, 1.0, 0.0, -2.0, -399.0, 0.0),
+    (0.0, 0.0, 2.0, -2.0, -381.0, -4421.0),
+    (1.0, 1.0, 1.0, 0.0, 351.0, 0.0),
+    (3.0, 0.0, -2.0, 0.0, -340.0, 0.0),
+    (4.0, 0.0, -3.0, 0.0, 330.0, 0.0),
+    (2.0, -1.0, 2.0, 0.0, 327.0, 0.0),
+    (0.0, 2.0, 1.0, 0.0, -323.0, 1165.0),
+    (1.0, 1.0, -1.0, 0.0, 299.0, 0.0),
+    (2.0, 0.0, 3.0, 0.0, 294.0, 0.0),
+    (2.0, 0.0, -1.0, -2.0, 0.0, 8752.0),
+];
+
+// Periodic terms for the latitude of the Moon (Meeus table 47.B), in
+// millionths of a degree.
+
+const B_TERMS: [(f64, f64, f64, f64, f64); 60] = [
+    (0.0, 0.0, 0.0, 1.0, 5128122.0),
+    (0.0, 0.0, 1.0, 1.0, 280602.0),
+    (0.0, 0.0, 1.0, -1.0, 277693.0),
+    (2.0, 0.0, 0.0, -1.0, 173237.0),
+    (2.0, 0.0, -1.0, 1.0, 55413.0),
+    (2.0, 0.0, -1.0, -1.0, 46271.0),
+    (2.0, 0.0, 0.0, 1.0, 32573.0),
+    (0.0, 0.0, 2.0, 1.0, 17198.0),
+    (2.0, 0.0, 1.0, -1.0, 9266.0),
+    (0.0, 0.0, 2.0, -1.0, 8822.0),
+    (2.0, -1.0, 0.0, -1.0, 8216.0),
+    (2.0, 0.0, -2.0, -1.0, 4324.0),
+    (2.0, 0.0, 1.0, 1.0, 4200.0),
+    (2.0, 1.0, 0.0, -1.0, -3359.0),
+    (2.0, -1.0, -1.0, 1.0, 2463.0),
+    (2.0, -1.0, 0.0, 1.0, 2211.0),
+    (2.0, -1.0, -1.0, -1.0, 2065.0),
+    (0.0, 1.0, -1.0, -1.0, -1870.0),
+    (4.0, 0.0, -1.0, -1.0, 1828.0),
+    (0.0, 1.0, 0.0, 1.0, -1794.0),
+    (0.0, 0.0, 0.0, 3.0, -1749.0),
+    (0.0, 1.0, -1.0, 1.0, -1565.0),
+    (1.0, 0.0, 0.0, 1.0, -1491.0),
+    (0.0, 1.0, 1.0, 1.0, -1475.0),
+    (0.0, 1.0, 1.0, -1.0, -1410.0),
+    (0.0, 1.0, 0.0, -1.0, -1344.0),
+    (1.0, 0.0, 0.0, -1.0, -1335.0),
+    (0.0, 0.0, 3.0, 1.0, 1107.0),
+    (4.0, 0.0, 0.0, -1.0, 1021.0),
+    (4.0, 0.0, -1.0, 1.0, 833.0),
+    (0.0, 0.0, 1.0, -3.0, 777.0),
+    (4.0, 0.0, -2.0, 1.0, 671.0),
+    (2.0, 0.0, 0.0, -3.0, 607.0),
+    (2.0, 0.0, 2.0, -1.0, 596.0),
+    (2.0, -1.0, 1.0, -1.0, 491.0),
+    (2.0, 0.0, -2.0, 1.0, -451.0),
+    (0.0, 0.0, 3.0, -1.0, 439.0),
+    (2.0, 0.0, 2.0, 1.0, 422.0),
+    (2.0, 0.0, -3.0, -1.0, 421.0),
+    (2.0, 1.0, -1.0, 1.0, -366.0),
+    (2.0, 1.0, 0.0, 1.0, -351.0),
+    (4.0, 0.0, 0.0, 1.0, 331.0),
+    (2.0, -1.0, 1.0, 1.0, 315.0),
+    (2.0, -2.0, 0.0, -1.0, 302.0),
+    (0.0, 0.0, 1.0, 3.0, -283.0),
+    (2.0, 1.0, 1.0, -1.0, -229.0),
+    (1.0, 1.0, 0.0, -1.0, 223.0),
+    (1.0, 1.0, 0.0, 1.0, 223.0),
+    (0.0, 1.0, -2.0, -1.0, -220.0),
+    (2.0, 1.0, -1.0, -1.0, -220.0),
+    (1.0, 0.0, 1.0, 1.0, -185.0),
+    (2.0, -1.0, -2.0, -1.0, 181.0),
+    (0.0, 1.0, 2.0, 1.0, -177.0),
+    (4.0, 0.0, -2.0, -1.0, 176.0),
+    (4.0, -1.0, -1.0, -1.0, 166.0),
+    (1.0, 0.0, 1.0, -1.0, -164.0),
+    (4.0, 0.0, 1.0, -1.0, 132.0),
+    (1.0, 0.0, -1.0, -1.0, -119.0),
+    (4.0, -1.0, 0.0, -1.0, 115.0),
+    (2.0, -2.0, 0.0, 1.0, 107.0),
+];
+
+// moon_position - ecliptic longitude, latitude and distance (km) of the
+// Moon at Julian Ephemeris Day jde
+
+pub(crate) fn moon_position(jde: f64) -> Ecliptic {
+    let t = (jde - 2451545.0) / 36525.0; // Julian centuries from J2000.0
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    // Moon's mean longitude and mean elongation, the mean anomalies of the
+    // Sun and Moon, and the Moon's argument of latitude
+    let lp = 218.3164477 + 481267.88123421 * t - 0.0015786 * t2 + t3 / 538841.0 - t4 / 65194000.0;
+    let d = 297.8501921 + 445267.1114034 * t - 0.0018819 * t2 + t3 / 545868.0 - t4 / 113065000.0;
+    let m = 357.5291092 + 35999.0502909 * t - 0.0001536 * t2 + t3 / 24490000.0;
+    let mp = 134.9633964 + 477198.8675055 * t + 0.0087414 * t2 + t3 / 69699.0 - t4 / 14712000.0;
+    let f = 93.2720950 + 483202.0175233 * t - 0.0036539 * t2 - t3 / 3526000.0 + t4 / 863310000.0;
+
+    // action of Venus, Jupiter and the flattening of the Earth
+    let a1 = 119.75 + 131.849 * t;
+    let a2 = 53.09 + 479264.290 * t;
+    let a3 = 313.45 + 481266.484 * t;
+
+    // eccentricity of the Earth's orbit, for the terms in M
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+
+    let mut sigma_l = 3958.0 * dsin(a1) + 1962.0 * dsin(lp - f) + 318.0 * dsin(a2);
+    let mut sigma_r = 0.0;
+    for (cd, cm, cmp, cf, l, r) in LR_TERMS {
+        let arg = cd * d + cm * m + cmp * mp + cf * f;
+        let ecc = e.powi(cm.abs() as i32);
+        sigma_l += l * ecc * dsin(arg);
+        sigma_r += r * ecc * dcos(arg);
+    }
+
+    let mut sigma_b = -2235.0 * dsin(lp)
+        + 382.0 * dsin(a3)
+        + 175.0 * dsin(a1 - f)
+        + 175.0 * dsin(a1 + f)
+        + 127.0 * dsin(lp - mp)
+        - 115.0 * dsin(lp + mp);
+    for (cd, cm, cmp, cf, b) in B_TERMS {
+        sigma_b += b * e.powi(cm.abs() as i32) * dsin(cd * d + cm * m + cmp * mp + cf * f);
+    }
+
+    Ecliptic {
+        lon: fixangle(lp + sigma_l / 1000000.0),
+        lat: sigma_b / 1000000.0,
+        dist: 385000.56 + sigma_r / 1000.0,
+    }
+}
+
+// moon_distance - distance of the Moon from the centre of the Earth, km,
+// at Julian Ephemeris Day jde
+
+pub(crate) fn moon_distance(jde: f64) -> f64 {
+    moon_position(jde).dist
+}
+
+// sun_position - apparent ecliptic longitude and distance (km) of the Sun
+// at Julian Ephemeris Day jde, corrected for aberration
+
+pub(crate) fn sun_position(jde: f64) -> Ecliptic {
+    let t = (jde - 2451545.0) / 36525.0;
+    let t2 = t * t;
+
+    let l0 = 280.46646 + 36000.76983 * t + 0.0003032 * t2; // mean longitude
+    let m = 357.52911 + 35999.05029 * t - 0.0001537 * t2; // mean anomaly
+    let e = 0.016708634 - 0.000042037 * t - 0.0000001267 * t2; // eccentricity
+
+    // equation of the centre
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t2) * dsin(m)
+        + (0.019993 - 0.000101 * t) * dsin(2.0 * m)
+        + 0.000289 * dsin(3.0 * m);
+    let r = 1.000001018 * (1.0 - e * e) / (1.0 + e * dcos(m + c)); // AU
+
+    Ecliptic {
+        lon: fixangle(l0 + c - 0.00569),
+        lat: 0.0,
+        dist: r * 149597870.7,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Meeus example 49.a: the new moon of 1977 February, k = -283
+    #[test]
+    fn example_49a() {
+        let jde = truephase(954.0, 0.0).unwrap();
+        assert!((jde - 2443192.65118).abs() < 0.000005, "{}", jde);
+    }
+}
@@ -0,0 +1,66 @@
+/*
+  PhaseModel - the algorithm behind every phase time the crate hands out.
+
+  phasehunt, phaselist and the Phases iterator only ever ask a model two
+  things: roughly when lunation k starts, and exactly when a given phase
+  of it happens.  Both are Julian Ephemeris Days (Terrestrial Time, see
+  timescale); k is counted from the new moon of 1900 January whatever
+  the model does inside.
+*/
+
+use super::{meanphase, meeus, truephase, PomError, SYNMONTH};
+
+pub trait PhaseModel {
+    // mean_phase - time of the mean new moon of lunation k, close enough
+    // to tell which lunation a date falls in
+    fn mean_phase(&self, k: f64) -> f64;
+
+    // true_phase - time of the phase selected by phase (0.0, 0.25, 0.5,
+    // 0.75) in lunation k
+    fn true_phase(&self, k: f64, phase: f64) -> Result<f64, PomError>;
+}
+
+impl<M: PhaseModel + ?Sized> PhaseModel for &M {
+    fn mean_phase(&self, k: f64) -> f64 {
+        (**self).mean_phase(k)
+    }
+
+    fn true_phase(&self, k: f64, phase: f64) -> Result<f64, PomError> {
+        (**self).true_phase(k, phase)
+    }
+}
+
+// DuffettSmith - Practical Astronomy With Your Calculator, as ported from
+// Astro::MoonPhase; what every function without a model uses
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DuffettSmith;
+
+impl PhaseModel for DuffettSmith {
+    fn mean_phase(&self, k: f64) -> f64 {
+        meanphase(2415020.75933 + SYNMONTH * k, k)
+    }
+
+    fn true_phase(&self, k: f64, phase: f64) -> Result<f64, PomError> {
+        truephase(k, phase)
+    }
+}
+
+// Meeus - Astronomical Algorithms chapter 49, see meeus::truephase
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Meeus;
+
+impl PhaseModel for Meeus {
+    fn mean_phase(&self, k: f64) -> f64 {
+        let k = k - 1237.0; // Meeus' k
+        let t = k / 1236.85;
+        let t2 = t * t;
+        2451550.09766 + 29.530588861 * k + 0.00015437 * t2 - 0.000000150 * t2 * t
+            + 0.00000000073 * t2 * t2
+    }
+
+    fn true_phase(&self, k: f64, phase: f64) -> Result<f64, PomError> {
+        meeus::truephase(k, phase)
+    }
+}
@@ -0,0 +1,131 @@
+/*
+  Traditional names of the full moons, as the almanacs give them.
+
+  Most go by the calendar month the full moon falls in.  The Harvest Moon
+  is instead the full moon nearest the autumn equinox, which may be in
+  either month around it, and the Hunter's Moon the one after; a
+  September full moon which is not the Harvest Moon is the Corn Moon.
+  South of the equator the names run six months out, the autumn equinox
+  being in March.
+*/
+
+use super::seasons::{season, SeasonKind};
+use super::{datetime, phases_before, phases_from, timestamp, PhaseEvent, PhaseKind, PomError};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+
+// FullMoonName - the names, in calendar order from January (in the
+// northern hemisphere)
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FullMoonName {
+    Wolf,
+    Snow,
+    Worm,
+    Pink,
+    Flower,
+    Strawberry,
+    Buck,
+    Sturgeon,
+    Corn,
+    Harvest,
+    Hunters,
+    Beaver,
+    Cold,
+}
+
+impl std::fmt::Display for FullMoonName {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            FullMoonName::Wolf => "Wolf",
+            FullMoonName::Snow => "Snow",
+            FullMoonName::Worm => "Worm",
+            FullMoonName::Pink => "Pink",
+            FullMoonName::Flower => "Flower",
+            FullMoonName::Strawberry => "Strawberry",
+            FullMoonName::Buck => "Buck",
+            FullMoonName::Sturgeon => "Sturgeon",
+            FullMoonName::Corn => "Corn",
+            FullMoonName::Harvest => "Harvest",
+            FullMoonName::Hunters => "Hunter's",
+            FullMoonName::Beaver => "Beaver",
+            FullMoonName::Cold => "Cold",
+        };
+        write!(f, "{} Moon", name)
+    }
+}
+
+// Hemisphere - which set of names to use
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    #[default]
+    Northern,
+    Southern,
+}
+
+// the names by month, January first, north of the equator
+const MONTHS: [FullMoonName; 12] = [
+    FullMoonName::Wolf,
+    FullMoonName::Snow,
+    FullMoonName::Worm,
+    FullMoonName::Pink,
+    FullMoonName::Flower,
+    FullMoonName::Strawberry,
+    FullMoonName::Buck,
+    FullMoonName::Sturgeon,
+    FullMoonName::Corn,
+    FullMoonName::Hunters,
+    FullMoonName::Beaver,
+    FullMoonName::Cold,
+];
+
+// full_moon_name - the name of a full moon, going by the calendar month
+// it falls in where it is kept (event's time zone).  Other phases have
+// none.
+
+pub fn full_moon_name<Tz: TimeZone>(
+    event: &PhaseEvent<DateTime<Tz>>,
+    hemisphere: Hemisphere,
+) -> Result<Option<FullMoonName>, PomError> {
+    if event.kind != PhaseKind::Full {
+        return Ok(None);
+    }
+    let t = timestamp(&event.time);
+
+    // the autumn equinox: September in the north, March in the south
+    let autumn = match hemisphere {
+        Hemisphere::Northern => SeasonKind::SeptemberEquinox,
+        Hemisphere::Southern => SeasonKind::MarchEquinox,
+    };
+    if harvest(t, autumn)? {
+        return Ok(Some(FullMoonName::Harvest));
+    }
+    if let Some(previous) = phases_before(t - 3600.0)?.find(|e| e.kind == PhaseKind::Full) {
+        if harvest(previous.time, autumn)? {
+            return Ok(Some(FullMoonName::Hunters));
+        }
+    }
+
+    let month = event.time.month0() as usize;
+    Ok(Some(match hemisphere {
+        Hemisphere::Northern => MONTHS[month],
+        Hemisphere::Southern => MONTHS[(month + 6) % 12],
+    }))
+}
+
+// harvest - whether the full moon at t (seconds since 1970) is the one
+// nearest the equinox autumn of its year
+
+fn harvest(t: f64, autumn: SeasonKind) -> Result<bool, PomError> {
+    let year = datetime(t, &Utc)?.year();
+    let equinox = season(year, autumn)?.time;
+    let before = phases_before(equinox)?.find(|e| e.kind == PhaseKind::Full);
+    let after = phases_from(equinox)?.find(|e| e.kind == PhaseKind::Full);
+    let nearest = match (before, after) {
+        (Some(b), Some(a)) if equinox - b.time < a.time - equinox => b.time,
+        (_, Some(a)) => a.time,
+        _ => return Ok(false),
+    };
+    // the event may come from another model, minutes apart
+    Ok((nearest - t).abs() < 86400.0)
+}
@@ -0,0 +1,184 @@
+/*
+  Observer - somewhere on the surface of the Earth.
+
+  The Moon is close enough that where you stand moves it against the
+  stars: up to about a degree (its horizontal parallax) when it sits on
+  the horizon.  An Observer turns the geocentric positions of the
+  position module into topocentric ones, as seen from that site.
+*/
+
+use super::position::{Equatorial, Horizontal};
+use super::{
+    checked, dcos, dsin, fixangle, jtime, phase, position, timescale, todeg, MoonPhase, PomError,
+    EARTH_FLAT, EARTH_RADIUS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    pub lat: f64,       // geodetic latitude, degrees north
+    pub lon: f64,       // longitude, degrees east of Greenwich
+    pub elevation: f64, // height above sea level, metres
+}
+
+// sidereal_time - Greenwich mean sidereal time, in degrees, at t
+// (seconds since 1970)
+
+pub fn sidereal_time(t: f64) -> Result<f64, PomError> {
+    Ok(gmst(timescale::tt_to_ut1(timescale::utc_to_tt(jtime(
+        checked(t)?,
+    )))))
+}
+
+// gmst - Greenwich mean sidereal time, in degrees, at Julian date jd (UT1)
+
+pub(crate) fn gmst(jd: f64) -> f64 {
+    let t = (jd - 2451545.0) / 36525.0;
+    fixangle(
+        280.46061837 + 360.98564736629 * (jd - 2451545.0) + 0.000387933 * t * t
+            - t * t * t / 38710000.0,
+    )
+}
+
+impl Observer {
+    pub fn new(lat: f64, lon: f64, elevation: f64) -> Result<Observer, PomError> {
+        if !(-90.0..=90.0).contains(&lat) || !lon.is_finite() || !elevation.is_finite() {
+            return Err(PomError::InvalidLocation);
+        }
+        Ok(Observer {
+            lat,
+            lon,
+            elevation,
+        })
+    }
+
+    // geocentric - rho sin phi' and rho cos phi', where the site is in
+    // Earth radii from the centre of the Earth towards the pole and
+    // equator
+    pub(crate) fn geocentric(&self) -> (f64, f64) {
+        let u = (EARTH_FLAT * self.lat.to_radians().tan()).atan();
+        let h = self.elevation / (EARTH_RADIUS * 1000.0);
+        (
+            EARTH_FLAT * u.sin() + h * dsin(self.lat),
+            u.cos() + h * dcos(self.lat),
+        )
+    }
+
+    // local_sidereal_time - local mean sidereal time, in degrees, at t
+    // (seconds since 1970)
+    pub fn local_sidereal_time(&self, t: f64) -> Result<f64, PomError> {
+        Ok(fixangle(sidereal_time(t)? + self.lon))
+    }
+
+    // topocentric - a geocentric position pos as seen from here at t
+    // (seconds since 1970)
+    pub fn topocentric(&self, pos: &Equatorial, t: f64) -> Result<Equatorial, PomError> {
+        Ok(self.topocentric_at(pos, self.local_sidereal_time(t)?))
+    }
+
+    // topocentric_at - the same for local sidereal time lst (degrees)
+    pub(crate) fn topocentric_at(&self, pos: &Equatorial, lst: f64) -> Equatorial {
+        let (rho_sin, rho_cos) = self.geocentric();
+        let r = pos.dist / EARTH_RADIUS; // distance in Earth radii
+        let h = lst - pos.ra; // hour angle
+
+        // move from the centre of the Earth to the site, in a frame
+        // turning with the Earth
+        let x = r * dcos(pos.dec) * dcos(h) - rho_cos;
+        let y = r * dcos(pos.dec) * dsin(h);
+        let z = r * dsin(pos.dec) - rho_sin;
+        let d = (x * x + y * y + z * z).sqrt();
+
+        Equatorial {
+            ra: fixangle(lst - todeg(y.atan2(x))),
+            dec: todeg((z / d).asin()),
+            dist: d * EARTH_RADIUS,
+        }
+    }
+
+    // altitude_at - height above the horizon, in degrees, of a topocentric
+    // position pos at local sidereal time lst, ignoring refraction
+    pub(crate) fn altitude_at(&self, pos: &Equatorial, lst: f64) -> f64 {
+        let h = lst - pos.ra;
+        todeg((dsin(self.lat) * dsin(pos.dec) + dcos(self.lat) * dcos(pos.dec) * dcos(h)).asin())
+    }
+
+    // horizontal_at - altitude and azimuth of a topocentric position pos at
+    // local sidereal time lst, ignoring refraction
+    pub(crate) fn horizontal_at(&self, pos: &Equatorial, lst: f64) -> Horizontal {
+        let h = lst - pos.ra;
+        let azimuth = todeg(
+            (-dcos(pos.dec) * dsin(h))
+                .atan2(dsin(pos.dec) * dcos(self.lat) - dcos(pos.dec) * dcos(h) * dsin(self.lat)),
+        );
+        Horizontal {
+            altitude: self.altitude_at(pos, lst),
+            azimuth: fixangle(azimuth),
+        }
+    }
+
+    // moon_position - where the Moon is, seen from here, at t (seconds
+    // since 1970)
+    pub fn moon_position(&self, t: f64) -> Result<Equatorial, PomError> {
+        self.topocentric(&position::moon_position(t)?.equatorial, t)
+    }
+
+    // sun_position - where the Sun is, seen from here, at t (seconds
+    // since 1970)
+    pub fn sun_position(&self, t: f64) -> Result<Equatorial, PomError> {
+        self.topocentric(&position::sun_position(t)?.equatorial, t)
+    }
+
+    // moon_altitude - height of the Moon above the horizon, in degrees, at t
+    // (seconds since 1970), ignoring refraction
+    pub fn moon_altitude(&self, t: f64) -> Result<f64, PomError> {
+        let lst = self.local_sidereal_time(t)?;
+        let moon = self.topocentric_at(&position::moon_position(t)?.equatorial, lst);
+        Ok(self.altitude_at(&moon, lst))
+    }
+
+    // moon_horizontal - altitude and azimuth of the Moon's centre at t
+    // (seconds since 1970), lifted by refraction if refraction is set
+    pub fn moon_horizontal(&self, t: f64, refraction: bool) -> Result<Horizontal, PomError> {
+        let lst = self.local_sidereal_time(t)?;
+        let moon = self.topocentric_at(&position::moon_position(t)?.equatorial, lst);
+        let mut pos = self.horizontal_at(&moon, lst);
+        if refraction {
+            pos.altitude += refract(pos.altitude);
+        }
+        Ok(pos)
+    }
+
+    // phase - phase() as seen from here: the illuminated fraction, the
+    // distance and the angular diameter are those of the topocentric
+    // Moon; the phase angle and age, which follow the Moon's orbit rather
+    // than the view of it, and the Sun are left geocentric
+    pub fn phase(&self, t: f64) -> Result<MoonPhase, PomError> {
+        let geo = phase(Some(t))?;
+        let moon = self.moon_position(t)?;
+        let sun = position::sun_position(t)?.equatorial;
+
+        // elongation of the Moon from the Sun, then the Moon's phase angle
+        let psi = (dsin(sun.dec) * dsin(moon.dec)
+            + dcos(sun.dec) * dcos(moon.dec) * dcos(sun.ra - moon.ra))
+        .clamp(-1.0, 1.0)
+        .acos();
+        let i = (sun.dist * psi.sin()).atan2(moon.dist - sun.dist * psi.cos());
+
+        Ok(MoonPhase {
+            illum: (1.0 + i.cos()) / 2.0,
+            dist: moon.dist,
+            ang: geo.ang * geo.dist / moon.dist,
+            ..geo
+        })
+    }
+}
+
+// refract - atmospheric refraction, in degrees, for a body whose true
+// altitude is altitude (degrees), at 10C and 1010 mb (Saemundsson, Sky
+// and Telescope 1986).  Nothing shows from further below the horizon than
+// refraction can lift, so it is taken no lower than a degree down.
+
+pub fn refract(altitude: f64) -> f64 {
+    let h = altitude.max(-1.0);
+    1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan() / 60.0
+}
@@ -0,0 +1,112 @@
+/*
+  Geocentric positions of the Moon and Sun.
+
+  These are the longitudes, latitude and distances phase() works out on
+  the way to the phase (Practical Astronomy With Your Calculator), turned
+  into right ascension and declination with the mean obliquity of the
+  ecliptic.  Angles are in degrees, distances in km; everything is
+  referred to the mean equinox of date.
+*/
+
+use super::{checked, dcos, dsin, fixangle, jtime, sunmoon, timescale, todeg, PomError};
+
+// Ecliptic - ecliptic co-ordinates
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ecliptic {
+    pub lon: f64,  // longitude, 0 to 360
+    pub lat: f64,  // latitude, -90 to 90
+    pub dist: f64, // distance from the centre of the Earth, km
+}
+
+// Equatorial - equatorial co-ordinates
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equatorial {
+    pub ra: f64,   // right ascension, 0 to 360 (divide by 15 for hours)
+    pub dec: f64,  // declination, -90 to 90
+    pub dist: f64, // distance from the centre of the Earth, km
+}
+
+// Horizontal - horizontal co-ordinates, for an observer
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Horizontal {
+    pub altitude: f64, // above the horizon, -90 to 90
+    pub azimuth: f64,  // from north through east, 0 to 360
+}
+
+// Position - the same place in both systems
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub ecliptic: Ecliptic,
+    pub equatorial: Equatorial,
+}
+
+impl Ecliptic {
+    // to_equatorial - rotate by the obliquity of the ecliptic eps (degrees)
+    pub fn to_equatorial(&self, eps: f64) -> Equatorial {
+        let ra = todeg(
+            (dsin(self.lon) * dcos(eps) - self.lat.to_radians().tan() * dsin(eps))
+                .atan2(dcos(self.lon)),
+        );
+        let dec = todeg(
+            (dsin(self.lat) * dcos(eps) + dcos(self.lat) * dsin(eps) * dsin(self.lon)).asin(),
+        );
+        Equatorial {
+            ra: fixangle(ra),
+            dec,
+            dist: self.dist,
+        }
+    }
+}
+
+// obliquity - mean obliquity of the ecliptic (degrees) at Julian
+// Ephemeris Day jde, after the IAU 1980 expression
+
+pub fn obliquity(jde: f64) -> f64 {
+    let t = (jde - 2451545.0) / 36525.0;
+    23.0 + 26.0 / 60.0 + 21.448 / 3600.0
+        - (46.8150 * t + 0.00059 * t * t - 0.001813 * t * t * t) / 3600.0
+}
+
+// moon_position - where the Moon is at t (seconds since 1970)
+
+pub fn moon_position(t: f64) -> Result<Position, PomError> {
+    moon_at(timescale::utc_to_tt(jtime(checked(t)?)))
+}
+
+// sun_position - where the Sun is at t (seconds since 1970)
+
+pub fn sun_position(t: f64) -> Result<Position, PomError> {
+    sun_at(timescale::utc_to_tt(jtime(checked(t)?)))
+}
+
+// moon_at, sun_at - the same for Julian Ephemeris Day jde
+
+pub(crate) fn moon_at(jde: f64) -> Result<Position, PomError> {
+    let sm = sunmoon(jde)?;
+    let ecliptic = Ecliptic {
+        lon: sm.lambdamoon,
+        lat: sm.betam,
+        dist: sm.moon_dist,
+    };
+    Ok(Position {
+        ecliptic,
+        equatorial: ecliptic.to_equatorial(obliquity(jde)),
+    })
+}
+
+pub(crate) fn sun_at(jde: f64) -> Result<Position, PomError> {
+    let sm = sunmoon(jde)?;
+    let ecliptic = Ecliptic {
+        lon: sm.lambdasun,
+        lat: 0.0,
+        dist: sm.sun_dist,
+    };
+    Ok(Position {
+        ecliptic,
+        equatorial: ecliptic.to_equatorial(obliquity(jde)),
+    })
+}
@@ -0,0 +1,298 @@
+/*
+  Rising, setting and transit of the Moon and Sun, and twilight.
+
+  Rather than solve for each event, the altitude (or hour angle) is
+  sampled every ten minutes through the day and each change of sign is
+  then narrowed down to the second by bisection.  The Moon moves too
+  quickly for the one-shot interpolation of the almanacs to hold for
+  long, and sampling copes without fuss with days on which it never
+  rises or sets, or does either twice.
+*/
+
+use super::observer::Observer;
+use super::{checked, datetime, position, timestamp, PomError, MANGSIZ, MSMAX, SUNANGSIZ, SUNSMAX};
+use chrono::{DateTime, TimeZone};
+
+const STEP: f64 = 600.0; // seconds between samples
+const REFRACTION: f64 = 34.0 / 60.0; // refraction at the horizon, degrees
+
+// depths of the Sun's centre below the horizon at which twilight ends
+const CIVIL: f64 = 6.0;
+const NAUTICAL: f64 = 12.0;
+const ASTRONOMICAL: f64 = 18.0;
+
+// RiseSet - the events of one day.  A body which neither rises nor sets
+// has stayed above the horizon (always_up) or below it (always_down)
+// the whole day.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiseSet<T = f64> {
+    pub rise: Option<T>,
+    pub set: Option<T>,
+    pub transit: Option<T>,       // upper transit, due south (or north)
+    pub lower_transit: Option<T>, // lower transit, below the pole
+    pub always_up: bool,
+    pub always_down: bool,
+}
+
+impl RiseSet {
+    // with_timezone - the same events as DateTimes in tz
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> Result<RiseSet<DateTime<Tz>>, PomError> {
+        let dt = |t: Option<f64>| t.map(|t| datetime(t, tz)).transpose();
+        Ok(RiseSet {
+            rise: dt(self.rise)?,
+            set: dt(self.set)?,
+            transit: dt(self.transit)?,
+            lower_transit: dt(self.lower_transit)?,
+            always_up: self.always_up,
+            always_down: self.always_down,
+        })
+    }
+}
+
+// moon_times - moonrise, moonset and transits at observer in the 24
+// hours from t (seconds since 1970)
+
+pub fn moon_times(t: f64, observer: &Observer) -> Result<RiseSet, PomError> {
+    moon_times_between(checked(t)?, t + 86400.0, observer)
+}
+
+// moon_times_tz - the same for the calendar day containing date, in
+// date's own time zone
+
+pub fn moon_times_tz<Tz: TimeZone>(
+    date: &DateTime<Tz>,
+    observer: &Observer,
+) -> Result<RiseSet<DateTime<Tz>>, PomError> {
+    let (start, end) = day(date)?;
+    moon_times_between(start, end, observer)?.with_timezone(&date.timezone())
+}
+
+fn moon_times_between(start: f64, end: f64, observer: &Observer) -> Result<RiseSet, PomError> {
+    // altitude of the Moon's upper limb above where it first shows,
+    // and its hour angle
+    let moon = |t: f64| -> Result<(f64, f64), PomError> {
+        let lst = observer.local_sidereal_time(t)?;
+        let pos = observer.topocentric_at(&position::moon_position(t)?.equatorial, lst);
+        let semidiameter = MANGSIZ * MSMAX / pos.dist / 2.0;
+        Ok((
+            observer.altitude_at(&pos, lst) + semidiameter + REFRACTION,
+            lst - pos.ra,
+        ))
+    };
+    events(start, end, moon)
+}
+
+// SunTimes - sunrise, sunset, solar noon and the twilights of one day.
+// Each twilight begins (dawn) and ends (dusk) with the Sun's centre 6,
+// 12 or 18 degrees below the horizon; any the Sun does not reach that
+// day are None.  With neither sunrise nor sunset it is polar day (the
+// Sun is up throughout) or polar night.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunTimes<T = f64> {
+    pub rise: Option<T>,
+    pub set: Option<T>,
+    pub noon: Option<T>,
+    pub civil_dawn: Option<T>,
+    pub civil_dusk: Option<T>,
+    pub nautical_dawn: Option<T>,
+    pub nautical_dusk: Option<T>,
+    pub astronomical_dawn: Option<T>,
+    pub astronomical_dusk: Option<T>,
+    pub polar_day: bool,
+    pub polar_night: bool,
+}
+
+impl SunTimes {
+    // with_timezone - the same events as DateTimes in tz
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> Result<SunTimes<DateTime<Tz>>, PomError> {
+        let dt = |t: Option<f64>| t.map(|t| datetime(t, tz)).transpose();
+        Ok(SunTimes {
+            rise: dt(self.rise)?,
+            set: dt(self.set)?,
+            noon: dt(self.noon)?,
+            civil_dawn: dt(self.civil_dawn)?,
+            civil_dusk: dt(self.civil_dusk)?,
+            nautical_dawn: dt(self.nautical_dawn)?,
+            nautical_dusk: dt(self.nautical_dusk)?,
+            astronomical_dawn: dt(self.astronomical_dawn)?,
+            astronomical_dusk: dt(self.astronomical_dusk)?,
+            polar_day: self.polar_day,
+            polar_night: self.polar_night,
+        })
+    }
+}
+
+// sun_times - sunrise, sunset, solar noon and twilight at observer in
+// the 24 hours from t (seconds since 1970)
+
+pub fn sun_times(t: f64, observer: &Observer) -> Result<SunTimes, PomError> {
+    sun_times_between(checked(t)?, t + 86400.0, observer)
+}
+
+// sun_times_tz - the same for the calendar day containing date, in
+// date's own time zone
+
+pub fn sun_times_tz<Tz: TimeZone>(
+    date: &DateTime<Tz>,
+    observer: &Observer,
+) -> Result<SunTimes<DateTime<Tz>>, PomError> {
+    let (start, end) = day(date)?;
+    sun_times_between(start, end, observer)?.with_timezone(&date.timezone())
+}
+
+fn sun_times_between(start: f64, end: f64, observer: &Observer) -> Result<SunTimes, PomError> {
+    // altitude of the Sun's centre, and its hour angle
+    let sun = |t: f64| -> Result<(f64, f64), PomError> {
+        let lst = observer.local_sidereal_time(t)?;
+        let pos = position::sun_position(t)?.equatorial;
+        Ok((observer.altitude_at(&pos, lst), lst - pos.ra))
+    };
+
+    // the upper limb on the horizon, refraction and all
+    let day = events(start, end, |t| {
+        let (altitude, h) = sun(t)?;
+        let semidiameter = SUNANGSIZ * SUNSMAX / position::sun_position(t)?.equatorial.dist / 2.0;
+        Ok((altitude + semidiameter + REFRACTION, h))
+    })?;
+
+    // dawn and dusk of a twilight depth degrees deep
+    let twilight = |depth: f64| -> Result<(Option<f64>, Option<f64>), PomError> {
+        let found = crossings(start, end, |t| Ok(sun(t)?.0 + depth))?;
+        Ok((
+            found.iter().find(|(_, up)| *up).map(|(t, _)| *t),
+            found.iter().find(|(_, up)| !*up).map(|(t, _)| *t),
+        ))
+    };
+    let (civil_dawn, civil_dusk) = twilight(CIVIL)?;
+    let (nautical_dawn, nautical_dusk) = twilight(NAUTICAL)?;
+    let (astronomical_dawn, astronomical_dusk) = twilight(ASTRONOMICAL)?;
+
+    Ok(SunTimes {
+        rise: day.rise,
+        set: day.set,
+        noon: day.transit,
+        civil_dawn,
+        civil_dusk,
+        nautical_dawn,
+        nautical_dusk,
+        astronomical_dawn,
+        astronomical_dusk,
+        polar_day: day.always_up,
+        polar_night: day.always_down,
+    })
+}
+
+// events - rise, set and transits between start and end for a body whose
+// altitude above its rising altitude and hour angle at t are given by
+// body
+
+pub(crate) fn events(
+    start: f64,
+    end: f64,
+    body: impl Fn(f64) -> Result<(f64, f64), PomError>,
+) -> Result<RiseSet, PomError> {
+    let altitude = |t: f64| Ok(body(t)?.0);
+    let upper = |t: f64| Ok(wrap180(body(t)?.1));
+    let lower = |t: f64| Ok(wrap180(body(t)?.1 - 180.0));
+
+    let horizon = crossings(start, end, altitude)?;
+    let rise = horizon.iter().find(|(_, up)| *up).map(|(t, _)| *t);
+    let set = horizon.iter().find(|(_, up)| !*up).map(|(t, _)| *t);
+    let above = horizon.is_empty() && altitude(start)? > 0.0;
+
+    Ok(RiseSet {
+        rise,
+        set,
+        transit: first_rising(start, end, upper)?,
+        lower_transit: first_rising(start, end, lower)?,
+        always_up: above,
+        always_down: horizon.is_empty() && !above,
+    })
+}
+
+// crossings - the times between start and end at which f changes sign,
+// with true where it goes from negative to positive
+
+pub(crate) fn crossings(
+    start: f64,
+    end: f64,
+    f: impl Fn(f64) -> Result<f64, PomError>,
+) -> Result<Vec<(f64, bool)>, PomError> {
+    let mut found = Vec::new();
+    let mut t0 = start;
+    let mut f0 = f(t0)?;
+    while t0 < end {
+        let t1 = (t0 + STEP).min(end);
+        let f1 = f(t1)?;
+        if (f0 < 0.0) != (f1 < 0.0) {
+            found.push((bisect(t0, f0, t1, &f)?, f0 < 0.0));
+        }
+        t0 = t1;
+        f0 = f1;
+    }
+    Ok(found)
+}
+
+// first_rising - the first time between start and end at which f goes
+// from negative to positive.  Hour angles wrap from +180 to -180, which
+// must not be taken for a transit.
+
+fn first_rising(
+    start: f64,
+    end: f64,
+    f: impl Fn(f64) -> Result<f64, PomError>,
+) -> Result<Option<f64>, PomError> {
+    Ok(crossings(start, end, f)?
+        .into_iter()
+        .find(|(_, rising)| *rising)
+        .map(|(t, _)| t))
+}
+
+// bisect - narrow a change of sign of f between t0 and t1 to a second
+
+pub(crate) fn bisect(
+    mut t0: f64,
+    f0: f64,
+    mut t1: f64,
+    f: impl Fn(f64) -> Result<f64, PomError>,
+) -> Result<f64, PomError> {
+    let negative = f0 < 0.0;
+    while t1 - t0 > 1.0 {
+        let tm = (t0 + t1) / 2.0;
+        if (f(tm)? < 0.0) == negative {
+            t0 = tm;
+        } else {
+            t1 = tm;
+        }
+    }
+    Ok((t0 + t1) / 2.0)
+}
+
+// wrap180 - an angle brought into -180 to +180 degrees
+
+fn wrap180(a: f64) -> f64 {
+    a - 360.0 * ((a + 180.0) / 360.0).floor()
+}
+
+// day - the calendar day containing date, in its own time zone, as
+// seconds since 1970 from its first to its last instant.  Where the
+// clocks go forward at midnight (as in Santiago or Havana) the day starts
+// when the gap ends, which is always on a quarter hour.
+
+pub(crate) fn day<Tz: TimeZone>(date: &DateTime<Tz>) -> Result<(f64, f64), PomError> {
+    let tz = date.timezone();
+    let midnight = |d: chrono::NaiveDate| {
+        (0..24 * 4)
+            .filter_map(|q| d.and_hms_opt(q / 4, q % 4 * 15, 0))
+            .find_map(|m| tz.from_local_datetime(&m).earliest())
+            .map(|m| timestamp(&m))
+            .ok_or(PomError::TimeOutOfRange(timestamp(date)))
+    };
+    let today = date.date_naive();
+    let tomorrow = today
+        .succ_opt()
+        .ok_or(PomError::TimeOutOfRange(timestamp(date)))?;
+    Ok((midnight(today)?, midnight(tomorrow)?))
+}
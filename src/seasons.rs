@@ -0,0 +1,200 @@
+/*
+  Equinoxes and solstices, after Meeus, Astronomical Algorithms chapter
+  27: the instants the Sun's apparent longitude reaches 0, 90, 180 and
+  270 degrees.
+
+  The mean instants of tables 27.A and 27.B are corrected by the periodic
+  terms of table 27.C, which brings them to within a minute or so between
+  the years 1951 and 2050, and not much worse over the four thousand years
+  from -1000 the tables cover.
+*/
+
+use super::{checked, datetime, jdaytosecs, timescale, PomError};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+
+// SeasonKind - the equinoxes and solstices, in the order they occur
+// within a year
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeasonKind {
+    MarchEquinox,
+    JuneSolstice,
+    SeptemberEquinox,
+    DecemberSolstice,
+}
+
+impl SeasonKind {
+    pub const ALL: [SeasonKind; 4] = [
+        SeasonKind::MarchEquinox,
+        SeasonKind::JuneSolstice,
+        SeasonKind::SeptemberEquinox,
+        SeasonKind::DecemberSolstice,
+    ];
+
+    // longitude - the Sun's apparent longitude at this moment, degrees
+    pub fn longitude(self) -> f64 {
+        match self {
+            SeasonKind::MarchEquinox => 0.0,
+            SeasonKind::JuneSolstice => 90.0,
+            SeasonKind::SeptemberEquinox => 180.0,
+            SeasonKind::DecemberSolstice => 270.0,
+        }
+    }
+}
+
+impl std::fmt::Display for SeasonKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SeasonKind::MarchEquinox => write!(f, "March equinox"),
+            SeasonKind::JuneSolstice => write!(f, "June solstice"),
+            SeasonKind::SeptemberEquinox => write!(f, "September equinox"),
+            SeasonKind::DecemberSolstice => write!(f, "December solstice"),
+        }
+    }
+}
+
+// SeasonEvent - one equinox or solstice: which, and when (seconds since
+// 1970, unless mapped into a chrono DateTime)
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonEvent<T = f64> {
+    pub kind: SeasonKind,
+    pub time: T,
+}
+
+impl SeasonEvent {
+    // with_timezone - the same event as a DateTime in tz
+    pub fn with_timezone<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+    ) -> Result<SeasonEvent<DateTime<Tz>>, PomError> {
+        Ok(SeasonEvent {
+            kind: self.kind,
+            time: datetime(self.time, tz)?,
+        })
+    }
+}
+
+impl<T> SeasonEvent<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SeasonEvent<U> {
+        SeasonEvent {
+            kind: self.kind,
+            time: f(self.time),
+        }
+    }
+}
+
+// Periodic terms (Meeus table 27.C): amplitude, phase and rate, degrees
+// per Julian century.
+
+const TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136),
+    (203.0, 337.23, 32964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112),
+    (156.0, 73.14, 45036.886),
+    (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934),
+    (74.0, 296.72, 3034.906),
+    (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562),
+    (44.0, 325.15, 31555.956),
+    (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328),
+    (17.0, 288.79, 4562.452),
+    (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921),
+    (12.0, 95.39, 14577.848),
+    (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259),
+    (9.0, 227.73, 1222.114),
+    (8.0, 15.45, 16859.074),
+];
+
+const FIRST: i32 = -1000; // first year the tables cover
+const LAST: i32 = 3000; // and the last
+
+// seasons - the March equinox, June solstice, September equinox and
+// December solstice of year, from -1000 to 3000
+
+pub fn seasons(year: i32) -> Result<[SeasonEvent; 4], PomError> {
+    if !(FIRST..=LAST).contains(&year) {
+        return Err(PomError::InvalidYear(year));
+    }
+    let jde = jde(year);
+    let mut events = [SeasonEvent {
+        kind: SeasonKind::MarchEquinox,
+        time: 0.0,
+    }; 4];
+    for (i, kind) in SeasonKind::ALL.into_iter().enumerate() {
+        events[i] = SeasonEvent {
+            kind,
+            time: checked(jdaytosecs(timescale::tt_to_utc(jde[i])))?,
+        };
+    }
+    Ok(events)
+}
+
+// season - one equinox or solstice of year
+
+pub fn season(year: i32, kind: SeasonKind) -> Result<SeasonEvent, PomError> {
+    Ok(seasons(year)?[kind as usize])
+}
+
+// seasonlist - every equinox and solstice on or after sdate but before
+// edate (seconds since 1970), in ascending order of time
+
+pub fn seasonlist(sdate: f64, edate: f64) -> Result<Vec<SeasonEvent>, PomError> {
+    let first = datetime(checked(sdate)?, &Utc)?.year();
+    let last = datetime(checked(edate)?, &Utc)?.year();
+    let mut events = Vec::new();
+    for year in first..=last {
+        events.extend(
+            seasons(year)?
+                .into_iter()
+                .filter(|e| e.time >= sdate && e.time < edate),
+        );
+    }
+    Ok(events)
+}
+
+// jde - the same as Julian Ephemeris Days
+
+fn jde(year: i32) -> [f64; 4] {
+    let (y, mean) = if year < 1000 {
+        (
+            year as f64 / 1000.0,
+            [
+                [1721139.29189, 365242.13740, 0.06134, 0.00111, -0.00071],
+                [1721233.25401, 365241.72562, -0.05323, 0.00907, 0.00025],
+                [1721325.70455, 365242.49558, -0.11677, -0.00297, 0.00074],
+                [1721414.39987, 365242.88257, -0.00769, -0.00933, -0.00006],
+            ],
+        )
+    } else {
+        (
+            (year - 2000) as f64 / 1000.0,
+            [
+                [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+                [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+                [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+                [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032],
+            ],
+        )
+    };
+
+    mean.map(|c| {
+        let jde0 = c[0] + y * (c[1] + y * (c[2] + y * (c[3] + y * c[4])));
+        let t = (jde0 - 2451545.0) / 36525.0;
+        let w = (35999.373 * t - 2.47).to_radians();
+        let dl = 1.0 + 0.0334 * w.cos() + 0.0007 * (2.0 * w).cos();
+        let s: f64 = TERMS
+            .iter()
+            .map(|(a, b, c)| a * (b + c * t).to_radians().cos())
+            .sum();
+        jde0 + 0.00001 * s / dl
+    })
+}
@@ -0,0 +1,220 @@
+/*
+  Time scales.
+
+  The phase and position formulas run on Terrestrial Time (TT, the old
+  Ephemeris Time), a uniform scale tied to atomic clocks.  Clocks and
+  seconds since 1970 run on UTC, which is kept within a second of the
+  Earth's rotation (UT1) by leap seconds.  The two drift apart by
+  Delta T = TT - UT1: about a minute today, hours in antiquity.
+
+  All of these take and return Julian dates.
+
+      TT  = TAI + 32.184s
+      TAI = UTC + leap seconds       (1972 onwards)
+      TT  = UT1 + Delta T
+
+  Before 1972, and after the last leap second we know about, UTC is
+  taken to be UT1.
+*/
+
+const TT_MINUS_TAI: f64 = 32.184; // seconds, by definition
+
+// TAI - UTC in whole seconds, from the Modified Julian Date (MJD =
+// JD - 2400000.5) on which each value came into force.
+
+const LEAP_SECONDS: [(f64, f64); 28] = [
+    (41317.0, 10.0), // 1972 Jan 1
+    (41499.0, 11.0), // 1972 Jul 1
+    (41683.0, 12.0), // 1973 Jan 1
+    (42048.0, 13.0), // 1974 Jan 1
+    (42413.0, 14.0), // 1975 Jan 1
+    (42778.0, 15.0), // 1976 Jan 1
+    (43144.0, 16.0), // 1977 Jan 1
+    (43509.0, 17.0), // 1978 Jan 1
+    (43874.0, 18.0), // 1979 Jan 1
+    (44239.0, 19.0), // 1980 Jan 1
+    (44786.0, 20.0), // 1981 Jul 1
+    (45151.0, 21.0), // 1982 Jul 1
+    (45516.0, 22.0), // 1983 Jul 1
+    (46247.0, 23.0), // 1985 Jul 1
+    (47161.0, 24.0), // 1988 Jan 1
+    (47892.0, 25.0), // 1990 Jan 1
+    (48257.0, 26.0), // 1991 Jan 1
+    (48804.0, 27.0), // 1992 Jul 1
+    (49169.0, 28.0), // 1993 Jul 1
+    (49534.0, 29.0), // 1994 Jul 1
+    (50083.0, 30.0), // 1996 Jan 1
+    (50630.0, 31.0), // 1997 Jul 1
+    (51179.0, 32.0), // 1999 Jan 1
+    (53736.0, 33.0), // 2006 Jan 1
+    (54832.0, 34.0), // 2009 Jan 1
+    (56109.0, 35.0), // 2012 Jul 1
+    (57204.0, 36.0), // 2015 Jul 1
+    (57754.0, 37.0), // 2017 Jan 1
+];
+
+// the table is known to be complete up to this MJD (IERS Bulletin C 71:
+// no leap second at the end of June 2026)
+const LEAP_SECONDS_EXPIRE: f64 = 61222.0; // 2026 Jul 1
+
+const MJD: f64 = 2400000.5;
+
+// tai_minus_utc - leap seconds in force at Julian date jd (UTC), or None
+// outside the years the table covers
+
+pub fn tai_minus_utc(jd: f64) -> Option<f64> {
+    let mjd = jd - MJD;
+    if !(LEAP_SECONDS[0].0..LEAP_SECONDS_EXPIRE).contains(&mjd) {
+        return None;
+    }
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|(start, _)| mjd >= *start)
+        .map(|(_, leap)| *leap)
+}
+
+// delta_t - TT - UT1 in seconds at Julian date jd.
+//
+// Inside the leap second table this is 32.184s plus the leap seconds,
+// UT1 - UTC never being more than 0.9s.  Elsewhere it comes from the
+// polynomials of Espenak and Meeus (Five Millennium Canon of Solar
+// Eclipses, NASA/TP-2006-214141), continued smoothly on from the last
+// leap second for future dates.
+
+pub fn delta_t(jd: f64) -> f64 {
+    let mjd = jd - MJD;
+    if let Some(leap) = tai_minus_utc(jd) {
+        TT_MINUS_TAI + leap
+    } else if mjd >= LEAP_SECONDS_EXPIRE {
+        let last = TT_MINUS_TAI + LEAP_SECONDS[LEAP_SECONDS.len() - 1].1;
+        last + espenak_meeus(year(jd)) - espenak_meeus(year(LEAP_SECONDS_EXPIRE + MJD))
+    } else {
+        espenak_meeus(year(jd))
+    }
+}
+
+// year - Julian date as a year and fraction
+
+fn year(jd: f64) -> f64 {
+    2000.0 + (jd - 2451545.0) / 365.25
+}
+
+// espenak_meeus - Delta T in seconds for year y (and fraction)
+
+fn espenak_meeus(y: f64) -> f64 {
+    if y < -500.0 {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u
+    } else if y < 500.0 {
+        let u = y / 100.0;
+        10583.6 - 1014.41 * u + 33.78311 * u.powi(2) - 5.952053 * u.powi(3) - 0.1798452 * u.powi(4)
+            + 0.022174192 * u.powi(5)
+            + 0.0090316521 * u.powi(6)
+    } else if y < 1600.0 {
+        let u = (y - 1000.0) / 100.0;
+        1574.2 - 556.01 * u + 71.23472 * u.powi(2) + 0.319781 * u.powi(3)
+            - 0.8503463 * u.powi(4)
+            - 0.005050998 * u.powi(5)
+            + 0.0083572073 * u.powi(6)
+    } else if y < 1700.0 {
+        let t = y - 1600.0;
+        120.0 - 0.9808 * t - 0.01532 * t.powi(2) + t.powi(3) / 7129.0
+    } else if y < 1800.0 {
+        let t = y - 1700.0;
+        8.83 + 0.1603 * t - 0.0059285 * t.powi(2) + 0.00013336 * t.powi(3) - t.powi(4) / 1174000.0
+    } else if y < 1860.0 {
+        let t = y - 1800.0;
+        13.72 - 0.332447 * t + 0.0068612 * t.powi(2) + 0.0041116 * t.powi(3)
+            - 0.00037436 * t.powi(4)
+            + 0.0000121272 * t.powi(5)
+            - 0.0000001699 * t.powi(6)
+            + 0.000000000875 * t.powi(7)
+    } else if y < 1900.0 {
+        let t = y - 1860.0;
+        7.62 + 0.5737 * t - 0.251754 * t.powi(2) + 0.01680668 * t.powi(3) - 0.0004473624 * t.powi(4)
+            + t.powi(5) / 233174.0
+    } else if y < 1920.0 {
+        let t = y - 1900.0;
+        -2.79 + 1.494119 * t - 0.0598939 * t.powi(2) + 0.0061966 * t.powi(3) - 0.000197 * t.powi(4)
+    } else if y < 1941.0 {
+        let t = y - 1920.0;
+        21.20 + 0.84493 * t - 0.076100 * t.powi(2) + 0.0020936 * t.powi(3)
+    } else if y < 1961.0 {
+        let t = y - 1950.0;
+        29.07 + 0.407 * t - t.powi(2) / 233.0 + t.powi(3) / 2547.0
+    } else if y < 1986.0 {
+        let t = y - 1975.0;
+        45.45 + 1.067 * t - t.powi(2) / 260.0 - t.powi(3) / 718.0
+    } else if y < 2005.0 {
+        let t = y - 2000.0;
+        63.86 + 0.3345 * t - 0.060374 * t.powi(2)
+            + 0.0017275 * t.powi(3)
+            + 0.000651814 * t.powi(4)
+            + 0.00002373599 * t.powi(5)
+    } else if y < 2050.0 {
+        let t = y - 2000.0;
+        62.92 + 0.32217 * t + 0.005589 * t.powi(2)
+    } else if y < 2150.0 {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u - 0.5628 * (2150.0 - y)
+    } else {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u
+    }
+}
+
+// utc_to_tai, tai_to_utc - add or remove the leap seconds (or, outside
+// the leap second years, go by way of UT1)
+
+pub fn utc_to_tai(jd: f64) -> f64 {
+    match tai_minus_utc(jd) {
+        Some(leap) => jd + leap / 86400.0,
+        None => tt_to_tai(ut1_to_tt(jd)),
+    }
+}
+
+pub fn tai_to_utc(jd: f64) -> f64 {
+    match tai_minus_utc(jd) {
+        Some(leap) => {
+            // look the leap seconds up again on the UTC date, in case one
+            // falls in between
+            let utc = jd - leap / 86400.0;
+            jd - tai_minus_utc(utc).unwrap_or(leap) / 86400.0
+        }
+        None => tt_to_ut1(tai_to_tt(jd)),
+    }
+}
+
+// tai_to_tt, tt_to_tai - a constant 32.184 seconds
+
+pub fn tai_to_tt(jd: f64) -> f64 {
+    jd + TT_MINUS_TAI / 86400.0
+}
+
+pub fn tt_to_tai(jd: f64) -> f64 {
+    jd - TT_MINUS_TAI / 86400.0
+}
+
+// ut1_to_tt, tt_to_ut1 - Delta T
+
+pub fn ut1_to_tt(jd: f64) -> f64 {
+    jd + delta_t(jd) / 86400.0
+}
+
+pub fn tt_to_ut1(jd: f64) -> f64 {
+    // Delta T changes by well under a second in a day, so one step is plenty
+    let guess = jd - delta_t(jd) / 86400.0;
+    jd - delta_t(guess) / 86400.0
+}
+
+// utc_to_tt, tt_to_utc - the conversions everything else in the crate
+// wants: seconds since 1970 are UTC, the formulas are TT
+
+pub fn utc_to_tt(jd: f64) -> f64 {
+    tai_to_tt(utc_to_tai(jd))
+}
+
+pub fn tt_to_utc(jd: f64) -> f64 {
+    tai_to_utc(tt_to_tai(jd))
+}